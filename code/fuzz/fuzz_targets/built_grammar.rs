@@ -0,0 +1,33 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use recursive_ascent_comparison::{built_variants, variant};
+
+// The parsers that `build.rs` generates from `arithmetic.grammar` should accept the same inputs as
+//   `paper::parse` of the first blog post, with the same reductions.
+fuzz_target!(|input: &str| {
+    let mut expected_reductions = vec![];
+    let expected = variant::<Vec<&'static str>>("part1::parse")(
+        &mut input.chars().peekable(),
+        &mut expected_reductions,
+        usize::MAX,
+    );
+    for (name, parse) in built_variants::<Vec<&'static str>>() {
+        let mut reductions = vec![];
+        let result = parse(&mut input.chars().peekable(), &mut reductions);
+        assert_eq!(
+            result.is_ok(),
+            expected.is_ok(),
+            "{} disagrees with paper::parse on {:?}",
+            name,
+            input
+        );
+        if result.is_ok() {
+            assert_eq!(
+                reductions, expected_reductions,
+                "{} reduces differently from paper::parse on {:?}",
+                name, input
+            );
+        }
+    }
+});
@@ -0,0 +1,24 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+use recursive_ascent_comparison::{
+    variants_with, Backend, UseArray, UseBits, UseBump, UseCachedTop, UseVec,
+};
+
+fn run<B: Backend>(input: &str) {
+    for (_, parse) in variants_with::<(), B>() {
+        let _ = parse(&mut input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH);
+    }
+}
+
+// Every variant with every stack backend and the `checked` feature, so reaching a label or stack
+//   top that a variant assumes to be impossible is a panic (and a crash for the fuzzer) instead of
+//   undefined behaviour.
+fuzz_target!(|input: &str| {
+    run::<UseVec>(input);
+    run::<UseCachedTop>(input);
+    run::<UseArray>(input);
+    run::<UseBump>(input);
+    run::<UseBits>(input);
+});
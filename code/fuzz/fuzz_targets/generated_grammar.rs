@@ -0,0 +1,31 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use recursive_ascent_comparison::{generated, variant};
+use recursive_ascent_core::eval::Evaluator;
+
+// The parser that `grammar!` generates should accept the same inputs as `paper::parse` of the
+//   first blog post, with the same value as the `Evaluator` with every `a` = 2.
+fuzz_target!(|input: &str| {
+    let mut evaluator = Evaluator::new(&[2]);
+    let expected = variant::<Evaluator>("part1::parse")(
+        &mut input.chars().peekable(),
+        &mut evaluator,
+        usize::MAX,
+    );
+    let result = generated::parse(input);
+    assert_eq!(
+        result.is_ok(),
+        expected.is_ok(),
+        "grammar! disagrees with paper::parse on {:?}",
+        input
+    );
+    if result.is_ok() {
+        assert_eq!(
+            result.ok(),
+            evaluator.value(),
+            "grammar! computes a different value for {:?}",
+            input
+        );
+    }
+});
@@ -0,0 +1,6 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use recursive_ascent_fuzz::agrees_with_paper_parse;
+
+fuzz_target!(|input: &str| agrees_with_paper_parse("part1::parse_chain_elim", input));
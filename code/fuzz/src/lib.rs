@@ -0,0 +1,28 @@
+//! Fuzz targets for the parser variants, one per variant plus `checked_variants`. The `seeds`
+//!   directory has valid and invalid expressions to start from, e.g.
+//!   `cargo fuzz run part2_parse_minpush corpus/part2_parse_minpush seeds`.
+
+use recursive_ascent_comparison::variant;
+
+/// Runs the variant on the input and checks it against `paper::parse` of the first blog post: the
+///   same result, and on success the same reductions. A panic here is a crash for the fuzzer.
+pub fn agrees_with_paper_parse(name: &str, input: &str) {
+    let parse = variant::<Vec<&'static str>>(name);
+    let reference = variant::<Vec<&'static str>>("part1::parse");
+    let (mut reductions, mut expected_reductions) = (vec![], vec![]);
+    // no depth limit, the variants push different amounts so they would hit it at different points
+    let result = parse(&mut input.chars().peekable(), &mut reductions, usize::MAX);
+    let expected = reference(
+        &mut input.chars().peekable(),
+        &mut expected_reductions,
+        usize::MAX,
+    );
+    assert_eq!(result, expected, "{} disagrees with paper::parse on {:?}", name, input);
+    if result.is_ok() {
+        assert_eq!(
+            reductions, expected_reductions,
+            "{} reduces differently from paper::parse on {:?}",
+            name, input
+        );
+    }
+}
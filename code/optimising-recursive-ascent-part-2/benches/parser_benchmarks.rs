@@ -1,5 +1,7 @@
 use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId};
-use optimizing_directly_executable_lr_parsers::{paper, parser};
+use optimising_recursive_ascent_part_2::{paper, parser};
+use recursive_ascent_core::stack::CachedTop;
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
 
 pub fn parse(c: &mut Criterion) {
     let sample_input = "a+a*(a+a)*a";
@@ -7,52 +9,52 @@ pub fn parse(c: &mut Criterion) {
         BenchmarkId::new("parse_reverse_goto", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        parser::parse_reverse_goto(&mut str.chars().peekable()).expect("sample_input should parse_reverse_goto just fine")));
+        parser::parse_reverse_goto::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_reverse_goto just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_asc_desc", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        parser::parse_asc_desc(&mut str.chars().peekable()).expect("sample_input should parse_asc_desc just fine")));
+        parser::parse_asc_desc::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_asc_desc just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_push_first", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        parser::parse_push_first(&mut str.chars().peekable()).expect("sample_input should parse_push_first just fine")));
+        parser::parse_push_first::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_push_first just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_minpush", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        parser::parse_minpush(&mut str.chars().peekable()).expect("sample_input should parse_minpush just fine")));
+        parser::parse_minpush::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_minpush just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_inline1", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        parser::parse_inline1(&mut str.chars().peekable()).expect("sample_input should parse_inline1 just fine")));
+        parser::parse_inline1::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_inline1 just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_inline2", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        parser::parse_inline2(&mut str.chars().peekable()).expect("sample_input should parse_inline2 just fine")));
+        parser::parse_inline2::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_inline2 just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_single_input_next1", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        parser::parse_single_input_next1(&mut str.chars().peekable()).expect("sample_input should parse_single_input_next1 just fine")));
+        parser::parse_single_input_next1::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_single_input_next1 just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_single_input_next", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        parser::parse_single_input_next(&mut str.chars().peekable()).expect("sample_input should parse_single_input_next just fine")));
+        parser::parse_single_input_next::<_, CachedTop<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_single_input_next just fine")));
     c.bench_with_input(
         BenchmarkId::new("paper::parse_parser_struct", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        paper::parse_parser_struct(&mut str.chars().peekable()).expect("sample_input should paper::parse_parser_struct just fine")));
+        paper::parse_parser_struct::<_, CachedTop<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should paper::parse_parser_struct just fine")));
     c.bench_with_input(
         BenchmarkId::new("paper::parse_single_match", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        paper::parse_single_match(&mut str.chars().peekable()).expect("sample_input should paper::parse_single_match just fine")));
+        paper::parse_single_match::<_, CachedTop<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should paper::parse_single_match just fine")));
 }
 
 criterion_group!(benches, parse);
@@ -0,0 +1,642 @@
+use recursive_ascent_core::grammar::{Grammar, Rule, Symbol};
+use recursive_ascent_core::lr0::{Item, Lr0};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The grammar from `lib.rs`, rule 0 is the start rule
+const RULES: [&str; 7] = [
+    "S = E",
+    "E = E + T",
+    "E = T",
+    "T = T * F",
+    "T = F",
+    "F = a",
+    "F = ( E )",
+];
+
+fn grammar() -> Grammar {
+    Grammar::parse(&RULES.join("\n")).expect("the rules of the blog posts should be a grammar")
+}
+
+fn item_string(lr0: &Lr0, item: Item) -> String {
+    let rule = lr0.rule(item);
+    let mut symbols: Vec<String> = rule.rhs.iter().map(|symbol| symbol.to_string()).collect();
+    symbols.insert(item.dot, "•".to_string());
+    format!("{} = {}", rule.lhs, symbols.join(" "))
+}
+
+/// The items of a state, split into those with the dot before a sort and the others
+fn items(lr0: &Lr0, state: usize, before_sort: bool) -> Vec<String> {
+    lr0.states[state]
+        .iter()
+        .filter(|&&item| matches!(lr0.next_symbol(item), Some(Symbol::Sort(_))) == before_sort)
+        .map(|&item| item_string(lr0, item))
+        .collect()
+}
+
+/// The edge annotations, written as in the blog post: move past `a` on the input, peek at `[C]`
+///   on the top of the stack, push `↓D` on the stack, pop `↑E` off the stack
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Action {
+    Shift(String),
+    Peek(String),
+    Push(String),
+    Pop(String),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Shift(symbol) => write!(f, "{}", symbol),
+            Action::Peek(s) => write!(f, "[{}]", s),
+            Action::Push(s) => write!(f, "↓{}", s),
+            Action::Pop(s) => write!(f, "↑{}", s),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Node {
+    pub name: String,
+    pub items: Vec<String>,
+}
+
+/// An edge can be taken with different stack contents, each option is shown on its own line
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+    pub options: Vec<Vec<Action>>,
+}
+
+/// An LR automaton, or one of the push-down automata underneath it that correspond to the stages of
+///   optimisation in `parser.rs`. `start` is entered with `start_actions`, `accept` is the final
+///   node without items. Inlined nodes point to the node they are inlined into in `inlined_into`,
+///   other nodes point to themselves.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Automaton {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    pub start: usize,
+    pub start_actions: Vec<Action>,
+    pub accept: usize,
+    pub inlined_into: Vec<usize>,
+}
+
+impl Automaton {
+    fn new() -> Automaton {
+        Automaton {
+            nodes: vec![],
+            edges: vec![],
+            start: 0,
+            start_actions: vec![],
+            accept: 0,
+            inlined_into: vec![],
+        }
+    }
+
+    fn add_node(&mut self, name: String, items: Vec<String>) -> usize {
+        self.nodes.push(Node { name, items });
+        self.inlined_into.push(self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    /// Adds the option to the edge between the nodes, or a new edge if there is none
+    fn add_edge(&mut self, from: usize, to: usize, option: Vec<Action>) {
+        match self.edges.iter_mut().find(|e| e.from == from && e.to == to) {
+            Some(edge) => {
+                if !edge.options.contains(&option) {
+                    edge.options.push(option);
+                }
+            }
+            None => self.edges.push(Edge {
+                from,
+                to,
+                options: vec![option],
+            }),
+        }
+    }
+
+    fn add_accept(&mut self, lr0: &Lr0, state_nodes: &[usize]) {
+        self.accept = self.add_node("accept".to_string(), vec![]);
+        for (state, &node) in state_nodes.iter().enumerate() {
+            if lr0.reductions(state).1 {
+                self.add_edge(node, self.accept, vec![]);
+            }
+        }
+    }
+
+    /// The root of the group of inlined nodes that a node is part of
+    pub fn root(&self, mut node: usize) -> usize {
+        while self.inlined_into[node] != node {
+            node = self.inlined_into[node];
+        }
+        node
+    }
+
+    /// Merges the nodes that have the same outgoing edges (after merging), by partition refinement.
+    ///   A merged node is named after the nodes in it, separated by slashes: `S0/S5/S6/S7`.
+    fn merge_equivalent(&mut self) {
+        let mut block: Vec<usize> = (0..self.nodes.len())
+            .map(|n| if n == self.accept { 1 } else { 0 })
+            .collect();
+        loop {
+            let mut signatures: HashMap<(usize, Vec<(String, usize)>), usize> = HashMap::new();
+            let mut new_block = vec![];
+            for n in 0..self.nodes.len() {
+                let mut signature: Vec<(String, usize)> = self
+                    .edges
+                    .iter()
+                    .filter(|e| e.from == n)
+                    .map(|e| (format!("{:?}", e.options), block[e.to]))
+                    .collect();
+                signature.sort();
+                let next = signatures.len();
+                new_block.push(*signatures.entry((block[n], signature)).or_insert(next));
+            }
+            let stable = new_block.iter().max() == block.iter().max();
+            block = new_block;
+            if stable {
+                break;
+            }
+        }
+
+        let mut merged = Automaton::new();
+        let mut node_of_block = HashMap::new();
+        for (n, node) in self.nodes.iter().enumerate() {
+            match node_of_block.get(&block[n]) {
+                Some(&m) => {
+                    let merged_node: &mut Node = &mut merged.nodes[m];
+                    merged_node.name.push('/');
+                    merged_node.name.push_str(&node.name);
+                    for item in &node.items {
+                        if !merged_node.items.contains(item) {
+                            merged_node.items.push(item.clone());
+                        }
+                    }
+                }
+                None => {
+                    let m = merged.add_node(node.name.clone(), node.items.clone());
+                    node_of_block.insert(block[n], m);
+                }
+            }
+        }
+        for edge in &self.edges {
+            for option in &edge.options {
+                merged.add_edge(
+                    node_of_block[&block[edge.from]],
+                    node_of_block[&block[edge.to]],
+                    option.clone(),
+                );
+            }
+        }
+        merged.start = node_of_block[&block[self.start]];
+        merged.start_actions = self.start_actions.clone();
+        merged.accept = node_of_block[&block[self.accept]];
+        *self = merged;
+    }
+
+    /// The sources of the edges into a node
+    fn incoming(&self, node: usize) -> Vec<usize> {
+        self.edges
+            .iter()
+            .filter(|e| e.to == node)
+            .map(|e| e.from)
+            .collect()
+    }
+}
+
+/// The LALR automaton, edges are labeled with the symbol. (Lookahead sets are not shown.)
+pub fn lalr() -> Automaton {
+    let grammar = grammar();
+    let lr0 = Lr0::new(&grammar);
+    let mut automaton = Automaton::new();
+    let nodes: Vec<usize> = (0..lr0.states.len())
+        .map(|state| {
+            let items = lr0.states[state]
+                .iter()
+                .map(|&item| item_string(&lr0, item))
+                .collect();
+            automaton.add_node(format!("S{}", state), items)
+        })
+        .collect();
+    for &(from, symbol, to) in lr0.transitions() {
+        let option = vec![Action::Shift(symbol.to_string())];
+        automaton.add_edge(nodes[from], nodes[to], option);
+    }
+    automaton.add_accept(&lr0, &nodes);
+    automaton
+}
+
+/// The push-down automaton underneath the LALR automaton (see `paper::parse` in part 1). Every
+///   state has its own goto node that receives the reduced sort on the stack.
+pub fn pda() -> Automaton {
+    let grammar = grammar();
+    let lr0 = Lr0::new(&grammar);
+    let mut automaton = Automaton::new();
+    let nodes: Vec<usize> = (0..lr0.states.len())
+        .map(|state| automaton.add_node(format!("S{}", state), items(&lr0, state, false)))
+        .collect();
+    let mut gotos = HashMap::new();
+    for state in 0..lr0.states.len() {
+        let items = items(&lr0, state, true);
+        if !items.is_empty() {
+            gotos.insert(state, automaton.add_node(format!("S{}Goto", state), items));
+        }
+    }
+    for &(from, symbol, to) in lr0.transitions() {
+        if let Symbol::Sort(sort) = symbol {
+            automaton.add_edge(gotos[&from], nodes[to], vec![Action::Pop(sort.clone())]);
+        } else {
+            let option = vec![
+                Action::Shift(symbol.to_string()),
+                Action::Push(from.to_string()),
+            ];
+            automaton.add_edge(nodes[from], nodes[to], option);
+        }
+    }
+    for (state, &node) in nodes.iter().enumerate() {
+        for rule in lr0.reductions(state).0 {
+            let Rule { lhs, rhs } = &grammar.rules()[rule];
+            for path in lr0.paths(state, rhs.len()) {
+                let mut option: Vec<Action> = path[1..rhs.len()]
+                    .iter()
+                    .rev()
+                    .map(|q| Action::Pop(q.to_string()))
+                    .collect();
+                option.push(Action::Peek(path[0].to_string()));
+                option.push(Action::Push(lhs.to_string()));
+                automaton.add_edge(node, gotos[&path[0]], option);
+            }
+        }
+    }
+    automaton.add_accept(&lr0, &nodes);
+    automaton
+}
+
+/// Builds the automata with one goto node per sort, that checks the state number on the stack.
+///   With `push_first` the state number that is pushed is that of the target state of a shift or
+///   goto, instead of the source state of a shift.
+fn sort_gotos(push_first: bool) -> Automaton {
+    let grammar = grammar();
+    let lr0 = Lr0::new(&grammar);
+    let mut automaton = Automaton::new();
+    let nodes: Vec<usize> = (0..lr0.states.len())
+        .map(|state| automaton.add_node(format!("S{}", state), items(&lr0, state, false)))
+        .collect();
+    let mut gotos = HashMap::new();
+    for sort in grammar.sorts() {
+        let mut items: Vec<String> = vec![];
+        for state in 0..lr0.states.len() {
+            for &item in &lr0.states[state] {
+                let item_string = item_string(&lr0, item);
+                let before_sort =
+                    matches!(lr0.next_symbol(item), Some(Symbol::Sort(s)) if s == sort);
+                if before_sort && !items.contains(&item_string) {
+                    items.push(item_string);
+                }
+            }
+        }
+        gotos.insert(sort, automaton.add_node(format!("{}Goto", sort), items));
+    }
+    if push_first {
+        automaton.start_actions = vec![Action::Push(0.to_string())];
+    }
+    for &(from, symbol, to) in lr0.transitions() {
+        if let Symbol::Sort(sort) = symbol {
+            let mut option = vec![Action::Peek(from.to_string())];
+            if push_first {
+                option.push(Action::Push(to.to_string()));
+            }
+            automaton.add_edge(gotos[sort.as_str()], nodes[to], option);
+        } else {
+            let pushed = if push_first { to } else { from };
+            let option = vec![
+                Action::Shift(symbol.to_string()),
+                Action::Push(pushed.to_string()),
+            ];
+            automaton.add_edge(nodes[from], nodes[to], option);
+        }
+    }
+    for (state, &node) in nodes.iter().enumerate() {
+        for rule in lr0.reductions(state).0 {
+            let Rule { lhs, rhs } = &grammar.rules()[rule];
+            for path in lr0.paths(state, rhs.len()) {
+                let popped = if push_first {
+                    &path[1..]
+                } else {
+                    &path[1..rhs.len()]
+                };
+                let option = popped
+                    .iter()
+                    .rev()
+                    .map(|q| Action::Pop(q.to_string()))
+                    .collect();
+                automaton.add_edge(node, gotos[lhs.as_str()], option);
+            }
+        }
+    }
+    automaton.add_accept(&lr0, &nodes);
+    automaton
+}
+
+/// Goto nodes per sort that peek at the state number on the stack, see `parser::parse_reverse_goto`
+pub fn reverse_goto() -> Automaton {
+    sort_gotos(false)
+}
+
+/// Pushing the target state number makes S0/S5/S6/S7 the same, so they are merged, see
+///   `parser::parse_push_first`
+pub fn push_first() -> Automaton {
+    let mut automaton = sort_gotos(true);
+    automaton.merge_equivalent();
+    automaton
+}
+
+/// Only the state numbers that are peeked at are pushed and popped, see `parser::parse_minpush`
+pub fn minimal_push() -> Automaton {
+    let mut automaton = push_first();
+    let mut peeked = vec![];
+    for edge in &automaton.edges {
+        for option in &edge.options {
+            for action in option {
+                if let Action::Peek(s) = action {
+                    peeked.push(s.clone());
+                }
+            }
+        }
+    }
+    let keep = |action: &Action| match action {
+        Action::Push(s) | Action::Pop(s) => peeked.contains(s),
+        _ => true,
+    };
+    automaton.start_actions.retain(keep);
+    for edge in &mut automaton.edges {
+        let mut options: Vec<Vec<Action>> = vec![];
+        for option in &edge.options {
+            let option: Vec<Action> = option.iter().filter(|a| keep(a)).cloned().collect();
+            if !options.contains(&option) {
+                options.push(option);
+            }
+        }
+        edge.options = options;
+    }
+    automaton
+}
+
+/// Every node with only one incoming edge is inlined into the source of that edge, see
+///   `parser::parse_inline1`
+pub fn max_inline_1() -> Automaton {
+    let mut automaton = minimal_push();
+    for node in 0..automaton.nodes.len() {
+        if node == automaton.start || node == automaton.accept {
+            continue;
+        }
+        if let [from] = automaton.incoming(node)[..] {
+            if from != node {
+                automaton.inlined_into[node] = from;
+            }
+        }
+    }
+    automaton
+}
+
+/// Groups of inlined nodes are inlined further when all edges into the group come from one other
+///   group, see `parser::parse_inline2`
+pub fn max_inline_2() -> Automaton {
+    let mut automaton = max_inline_1();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for node in 0..automaton.nodes.len() {
+            if automaton.root(node) != node || node == automaton.start || node == automaton.accept {
+                continue;
+            }
+            let mut from_groups: Vec<usize> = automaton
+                .incoming(node)
+                .iter()
+                .map(|&from| automaton.root(from))
+                .collect();
+            from_groups.sort();
+            from_groups.dedup();
+            if let [group] = from_groups[..] {
+                if group != node {
+                    automaton.inlined_into[node] = group;
+                    changed = true;
+                }
+            }
+        }
+    }
+    automaton
+}
+
+fn option_string(option: &[Action]) -> String {
+    let mut s = String::new();
+    for action in option {
+        s.push_str(&action.to_string());
+    }
+    s
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Automaton {
+    /// Graphviz DOT in the style of `pda-automaton.gv`, with the items as HTML table rows, the
+    ///   options of an edge on separate lines, and groups of inlined nodes as clusters.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        dot.push_str("digraph {\n");
+        dot.push_str("    bgcolor=\"transparent\";\n");
+        dot.push_str("    node [shape=none, fontcolor=\"#ddd\", color=\"#ddd\"];\n");
+        dot.push_str("    edge [fontcolor=\"#ddd\", color=\"#ddd\"];\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str("    start [label=\"\", width=0];\n");
+        for (n, node) in self.nodes.iter().enumerate() {
+            if n == self.accept {
+                dot.push_str(&format!(
+                    "    n{} [shape=doublecircle, label=\"{}\"];\n",
+                    n, node.name
+                ));
+                continue;
+            }
+            dot.push_str(&format!(
+                "    n{} [label=<<table cellborder=\"0\" bgcolor=\"#333\">\n",
+                n
+            ));
+            dot.push_str(&format!(
+                "        <tr><td>{}</td></tr>\n",
+                escape(&node.name)
+            ));
+            for item in &node.items {
+                dot.push_str(&format!(
+                    "        <tr><td align=\"left\">{}</td></tr>\n",
+                    escape(item)
+                ));
+            }
+            dot.push_str("    </table>>];\n");
+        }
+        for root in 0..self.nodes.len() {
+            let members: Vec<usize> = (0..self.nodes.len())
+                .filter(|&n| self.root(n) == root)
+                .collect();
+            if members.len() > 1 {
+                dot.push_str(&format!("    subgraph cluster_n{} {{\n", root));
+                dot.push_str("        style=dashed;\n        color=\"#ddd\";\n");
+                for n in members {
+                    dot.push_str(&format!("        n{};\n", n));
+                }
+                dot.push_str("    }\n");
+            }
+        }
+        dot.push_str(&format!(
+            "    start -> n{} [label=\"{}\"];\n",
+            self.start,
+            option_string(&self.start_actions)
+        ));
+        for edge in &self.edges {
+            let label: Vec<String> = edge.options.iter().map(|o| option_string(o)).collect();
+            dot.push_str(&format!(
+                "    n{} -> n{} [label=\"{}\"];\n",
+                edge.from,
+                edge.to,
+                label.join("\\n")
+            ));
+        }
+        dot.push('}');
+        dot
+    }
+
+    /// draw.io XML in the style of the diagrams in the blog post. Nodes are placed in columns by
+    ///   their distance from the start, inlined nodes are stacked in a container with the node they
+    ///   are inlined into. Use Arrange > Layout in draw.io for a nicer layout.
+    pub fn to_drawio(&self) -> String {
+        const WIDTH: usize = 180;
+        const GAP: usize = 60;
+        let height = |n: usize| 40 + 20 * self.nodes[n].items.len();
+
+        // distance from the start, per group
+        let mut column = vec![usize::MAX; self.nodes.len()];
+        column[self.root(self.start)] = 0;
+        let mut todo = vec![self.root(self.start)];
+        while !todo.is_empty() {
+            let mut next = vec![];
+            for group in todo {
+                for edge in self.edges.iter().filter(|e| self.root(e.from) == group) {
+                    let to = self.root(edge.to);
+                    if column[to] == usize::MAX {
+                        column[to] = column[group] + 1;
+                        next.push(to);
+                    }
+                }
+            }
+            todo = next;
+        }
+
+        let mut cells = String::new();
+        let mut column_y: HashMap<usize, usize> = HashMap::new();
+        for group in (0..self.nodes.len()).filter(|&n| self.root(n) == n) {
+            let members: Vec<usize> = (0..self.nodes.len())
+                .filter(|&n| self.root(n) == group)
+                .collect();
+            let col = if column[group] == usize::MAX {
+                0
+            } else {
+                column[group]
+            };
+            let group_height: usize =
+                members.iter().map(|&n| height(n) + GAP / 2).sum::<usize>() - GAP / 2;
+            let y = column_y.entry(col).or_insert(0);
+            let (x, group_y) = (col * (WIDTH + 2 * GAP), *y);
+            *y += group_height + GAP;
+
+            let (parent, mut member_y) = if members.len() > 1 {
+                cells.push_str(&format!(
+                    "        <mxCell id=\"g{}\" value=\"\" style=\"rounded=0;whiteSpace=wrap;html=1;fillColor=none;strokeColor=#FFFFFF;dashed=1;container=1;\" parent=\"1\" vertex=\"1\">\n          <mxGeometry x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" as=\"geometry\" />\n        </mxCell>\n",
+                    group,
+                    x,
+                    group_y,
+                    WIDTH + GAP / 2,
+                    group_height + GAP / 2
+                ));
+                (format!("g{}", group), GAP / 4)
+            } else {
+                ("1".to_string(), group_y)
+            };
+            let member_x = if members.len() > 1 { GAP / 4 } else { x };
+            for n in members {
+                let node = &self.nodes[n];
+                let mut value = format!("<div>{}</div>", escape(&node.name));
+                if !node.items.is_empty() {
+                    value.push_str("<div align=\"left\"><br></div>");
+                }
+                for item in &node.items {
+                    value.push_str(&format!("<div align=\"left\">{}</div>", escape(item)));
+                }
+                let shape = if n == self.accept {
+                    "ellipse;"
+                } else {
+                    "rounded=0;"
+                };
+                cells.push_str(&format!(
+                    "        <mxCell id=\"n{}\" value=\"{}\" style=\"{}whiteSpace=wrap;html=1;fontFamily=monospace;spacingLeft=0;fontSize=15;fillColor=#333333;fontColor=#FFFFFF;verticalAlign=top;\" parent=\"{}\" vertex=\"1\">\n          <mxGeometry x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" as=\"geometry\" />\n        </mxCell>\n",
+                    n,
+                    escape(&value),
+                    shape,
+                    parent,
+                    member_x,
+                    member_y,
+                    WIDTH,
+                    height(n)
+                ));
+                member_y += height(n) + GAP / 2;
+            }
+        }
+
+        let edge_style = "rounded=0;orthogonalLoop=1;jettySize=auto;html=1;fontFamily=monospace;spacingLeft=0;fontSize=15;fontColor=#FFFFFF;labelBackgroundColor=#444444;";
+        cells.push_str(&format!(
+            "        <mxCell id=\"start\" value=\"\" style=\"ellipse;fillColor=#FFFFFF;strokeColor=none;\" parent=\"1\" vertex=\"1\">\n          <mxGeometry x=\"{}\" y=\"-{}\" width=\"10\" height=\"10\" as=\"geometry\" />\n        </mxCell>\n",
+            WIDTH / 2,
+            GAP
+        ));
+        cells.push_str(&format!(
+            "        <mxCell id=\"e_start\" value=\"{}\" style=\"{}\" parent=\"1\" source=\"start\" target=\"n{}\" edge=\"1\">\n          <mxGeometry relative=\"1\" as=\"geometry\" />\n        </mxCell>\n",
+            escape(&option_string(&self.start_actions)),
+            edge_style,
+            self.start
+        ));
+        for (i, edge) in self.edges.iter().enumerate() {
+            let label: Vec<String> = edge
+                .options
+                .iter()
+                .map(|o| escape(&option_string(o)))
+                .collect();
+            cells.push_str(&format!(
+                "        <mxCell id=\"e{}\" value=\"{}\" style=\"{}\" parent=\"1\" source=\"n{}\" target=\"n{}\" edge=\"1\">\n          <mxGeometry relative=\"1\" as=\"geometry\" />\n        </mxCell>\n",
+                i,
+                escape(&label.join("<br>")),
+                edge_style,
+                edge.from,
+                edge.to
+            ));
+        }
+
+        let mut xml = String::new();
+        xml.push_str("<mxfile>\n");
+        xml.push_str("  <diagram name=\"Page-1\">\n");
+        xml.push_str("    <mxGraphModel grid=\"1\" gridSize=\"10\" guides=\"1\" tooltips=\"1\" connect=\"1\" arrows=\"1\" fold=\"1\" page=\"1\" pageScale=\"1\" pageWidth=\"827\" pageHeight=\"1169\" background=\"#444444\" math=\"0\" shadow=\"0\">\n");
+        xml.push_str("      <root>\n");
+        xml.push_str("        <mxCell id=\"0\" />\n");
+        xml.push_str("        <mxCell id=\"1\" parent=\"0\" />\n");
+        xml.push_str(&cells);
+        xml.push_str("      </root>\n");
+        xml.push_str("    </mxGraphModel>\n");
+        xml.push_str("  </diagram>\n");
+        xml.push_str("</mxfile>\n");
+        xml
+    }
+}
@@ -0,0 +1,27 @@
+use optimising_recursive_ascent_part_2::automaton;
+use std::path::PathBuf;
+
+/// Writes the LR automaton and the push-down automata of each optimisation stage as Graphviz DOT
+///   and draw.io files into the directory given as argument, with the file names used in
+///   `content/optimising-lr-automata`.
+fn main() {
+    let Some(dir) = std::env::args().nth(1).map(PathBuf::from) else {
+        eprintln!("Usage: automaton_diagrams <output directory>");
+        std::process::exit(1);
+    };
+    let stages = [
+        ("lalr-automaton", automaton::lalr()),
+        ("pda-automaton", automaton::pda()),
+        ("reverse-goto", automaton::reverse_goto()),
+        ("push-first", automaton::push_first()),
+        ("minimal-push", automaton::minimal_push()),
+        ("max-inline-1", automaton::max_inline_1()),
+        ("max-inline-2", automaton::max_inline_2()),
+    ];
+    for (name, automaton) in stages {
+        std::fs::write(dir.join(format!("{}.gv", name)), automaton.to_dot())
+            .expect("should be able to write the .gv file");
+        std::fs::write(dir.join(format!("{}.drawio", name)), automaton.to_drawio())
+            .expect("should be able to write the .drawio file");
+    }
+}
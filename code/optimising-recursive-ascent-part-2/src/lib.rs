@@ -1,98 +1,99 @@
-pub mod parser;
+pub mod automaton;
 pub mod paper;
+pub mod parser;
+pub mod sequence;
 
-use std::iter::Peekable;
-use std::str::Chars;
-
-/*
-S = E
-E = E + T
-E = T
-T = T * F
-T = F
-F = a
-F = ( E )
- */
-
-pub type Iter<'a> = Peekable<Chars<'a>>;
-
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum Sort {
-    // S,
-    E,
-    T,
-    F,
-}
-
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum State {
-    S0,
-    S1,
-    S2,
-    S3,
-    S4,
-    S5,
-    S6,
-    S7,
-    S8,
-    S9,
-    S10,
-    S11,
-    EGoto,
-    TGoto,
-    FGoto,
-}
-
-#[inline(never)]
-fn outprod(_rule: &str) {
-    // a semantic action
-    // eprintln!("{}", rule)
-}
-
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum Error {
-    EOF,
-    Unexpected(char),
-}
+use recursive_ascent_core::stack::{ParseStack, StackValue};
+use recursive_ascent_core::{DepthLimit, Error, Iter, Snapshot, State};
 
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
 pub enum StackLabel {
-    SL0,
-    SL5,
-    SL6,
-    SL7,
+    SL0 = 0,
+    SL5 = 5,
+    SL6 = 6,
+    SL7 = 7,
 }
 
-pub struct Parser {
-    stack: Vec<StackLabel>,
-    stack_last: StackLabel,
+pub struct Parser<S> {
+    /// Never empty, the bottom is `SL0`
+    stack: S,
     pub label: State,
 }
 
-impl Default for Parser {
+impl<S: ParseStack<Value = StackLabel>> Default for Parser<S> {
     fn default() -> Self {
         Parser {
-            stack: vec![],
-            stack_last: StackLabel::SL0,
+            stack: S::from_slice(&[StackLabel::SL0]),
             label: State::S0,
         }
     }
 }
 
-impl Parser {
+impl<S: ParseStack<Value = StackLabel>> Parser<S> {
+    /// Back to the start for the next expression, keeping the allocation of the stack
+    pub fn reset(&mut self) {
+        self.stack.truncate(1);
+        self.label = State::S0;
+    }
+
     #[inline(always)]
-    fn push(&mut self, n: StackLabel) {
-        self.stack.push(self.stack_last);
-        self.stack_last = n;
+    fn push(&mut self, n: StackLabel, limit: &DepthLimit, input: &Iter) -> Result<(), Error> {
+        limit.push(&mut self.stack, n, input)
     }
 
     #[inline(always)]
     fn pop(&mut self) {
-        self.stack_last = unsafe { self.stack.pop().unwrap_unchecked() };
+        if cfg!(feature = "checked") && self.stack.depth() < 2 {
+            panic!("{:?} popped the bottom of the stack", self.label);
+        }
+        self.stack.pop();
     }
 
+    /// The top of the stack, which should be one of `expected`, see [`recursive_ascent_core::top`]
     #[inline(always)]
-    fn peek(&self) -> StackLabel {
-        self.stack_last
+    fn peek(&self, expected: &[StackLabel]) -> StackLabel {
+        if cfg!(feature = "checked") {
+            recursive_ascent_core::top(&self.label, &self.stack, expected)
+        } else {
+            unsafe { self.stack.peek().unwrap_unchecked() }
+        }
     }
-}
\ No newline at end of file
+}
+
+/// For the snapshots of the stack when tracing
+impl From<StackLabel> for i32 {
+    fn from(label: StackLabel) -> i32 {
+        label as i32
+    }
+}
+
+/// The four labels fit in two bits
+impl StackValue for StackLabel {
+    const BITS: u32 = 2;
+
+    #[inline(always)]
+    fn to_bits(self) -> u64 {
+        match self {
+            StackLabel::SL0 => 0,
+            StackLabel::SL5 => 1,
+            StackLabel::SL6 => 2,
+            StackLabel::SL7 => 3,
+        }
+    }
+
+    #[inline(always)]
+    fn from_bits(bits: u64) -> Self {
+        match bits {
+            0 => StackLabel::SL0,
+            1 => StackLabel::SL5,
+            2 => StackLabel::SL6,
+            _ => StackLabel::SL7,
+        }
+    }
+}
+
+impl<S: ParseStack> Snapshot for Parser<S> {
+    fn snapshot(&self) -> Vec<i32> {
+        self.stack.snapshot()
+    }
+}
@@ -1,21 +1,23 @@
-use optimizing_directly_executable_lr_parsers::parser;
+use optimising_recursive_ascent_part_2::parser;
+use recursive_ascent_core::stack::CachedTop;
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
 
 fn main() {
     let sample_input = "a+a*(a+a)*a";
     println!("parse_reverse_goto");
-    parser::parse_reverse_goto(&mut sample_input.chars().peekable()).expect("sample_input should parse_reverse_goto just fine");
+    parser::parse_reverse_goto::<_, Vec<_>>(&mut sample_input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_reverse_goto just fine");
     println!("parse_asc_desc");
-    parser::parse_reverse_goto(&mut sample_input.chars().peekable()).expect("sample_input should parse_asc_desc just fine");
+    parser::parse_reverse_goto::<_, Vec<_>>(&mut sample_input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_asc_desc just fine");
     println!("parse_push_first");
-    parser::parse_push_first(&mut sample_input.chars().peekable()).expect("sample_input should parse_push_first just fine");
+    parser::parse_push_first::<_, Vec<_>>(&mut sample_input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_push_first just fine");
     println!("parse_minpush");
-    parser::parse_minpush(&mut sample_input.chars().peekable()).expect("sample_input should parse_minpush just fine");
+    parser::parse_minpush::<_, Vec<_>>(&mut sample_input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_minpush just fine");
     println!("parse_inline1");
-    parser::parse_inline1(&mut sample_input.chars().peekable()).expect("sample_input should parse_inline1 just fine");
+    parser::parse_inline1::<_, Vec<_>>(&mut sample_input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_inline1 just fine");
     println!("parse_inline2");
-    parser::parse_inline2(&mut sample_input.chars().peekable()).expect("sample_input should parse_inline2 just fine");
+    parser::parse_inline2::<_, Vec<_>>(&mut sample_input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_inline2 just fine");
     println!("parse_single_input_next1");
-    parser::parse_single_input_next1(&mut sample_input.chars().peekable()).expect("sample_input should parse_single_input_next1 just fine");
+    parser::parse_single_input_next1::<_, Vec<_>>(&mut sample_input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_single_input_next1 just fine");
     println!("parse_single_input_next");
-    parser::parse_single_input_next(&mut sample_input.chars().peekable()).expect("sample_input should parse_single_input_next just fine");
+    parser::parse_single_input_next::<_, CachedTop<_>>(&mut sample_input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_single_input_next just fine");
 }
@@ -1,49 +1,56 @@
-use crate::{outprod, Error, Iter, State, StackLabel, Parser};
-use std::hint::unreachable_unchecked;
+use crate::{Parser, StackLabel};
+use recursive_ascent_core::stack::ParseStack;
+use recursive_ascent_core::{peek, step, unreachable_label, Actions, DepthLimit, Error, Iter, State};
 
 /// Use the `Parser` struct to check if that has any influence (it doesn't), as well as inlining
 ///  `TGoto` since the code is trivial and flipping the matches in `EGoto` to get everything in the
 ///  same shape for the next variant.
-pub fn parse_parser_struct(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_parser_struct<A: Actions, S: ParseStack<Value = StackLabel>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
     use StackLabel::*;
 
-    let mut p = Parser::default();
+    let limit = DepthLimit::new(max_depth, 1, input);
+    let mut p = Parser::<S>::default();
     loop {
+        step(actions, &p.label, &p, input);
         match p.label {
             S0 => match input.next() {
                 Some('a') => {
-                    p.push(SL0);
-                    outprod("F = a");
-                    outprod("T = F");
+                    p.push(SL0, &limit, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     p.label = S2;
                 },
                 Some('(') => {
-                    p.push(SL0);
+                    p.push(SL0, &limit, input)?;
                     p.label = S5;
                 },
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             }
-            S2 => match input.peek() {
+            S2 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
                     p.label = S7;
                 },
                 _ => {
-                    outprod("E = T");
+                    actions.outprod("E = T");
                     p.label = EGoto
                 },
             }
             S5 => match input.next() {
                 Some('a') => {
-                    p.push(SL5);
-                    outprod("F = a");
-                    outprod("T = F");
+                    p.push(SL5, &limit, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     p.label = S2;
                 }
                 Some('(') => {
-                    p.push(SL5);
+                    p.push(SL5, &limit, input)?;
                     p.label = S5; // (self)
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -51,12 +58,13 @@ pub fn parse_parser_struct(input: &mut Iter) -> Result<(), Error> {
             },
             S6 => match input.next() {
                 Some('a') => {
-                    p.push(SL6);
-                    outprod("F = a");
+                    p.push(SL6, &limit, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     p.label = S9;
                 }
                 Some('(') => {
-                    p.push(SL6);
+                    p.push(SL6, &limit, input)?;
                     p.label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -64,32 +72,32 @@ pub fn parse_parser_struct(input: &mut Iter) -> Result<(), Error> {
             },
             S7 => match input.next() {
                 Some('a') => {
-                    p.push(SL7);
-                    outprod("F = a");
+                    p.push(SL7, &limit, input)?;
+                    actions.outprod("F = a");
                     p.label = S10
                 }
                 Some('(') => {
-                    p.push(SL7);
+                    p.push(SL7, &limit, input)?;
                     p.label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S9 => match input.peek() {
+            S9 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
                     p.label = S7;
                 }
                 _ => {
-                    let _ = p.pop(); // 6
-                    outprod("E = E + T");
+                    p.pop(); // 6
+                    actions.outprod("E = E + T");
                     p.label = EGoto
                 }
             },
             S10 => {
-                let _ = p.pop(); // 7
-                outprod("T = T * F");
-                match p.peek() {
+                p.pop(); // 7
+                actions.outprod("T = T * F");
+                match p.peek(&[SL0, SL5, SL6]) {
                     SL6 => p.label = S9,
                     _ => p.label = S2,
                 }
@@ -98,15 +106,15 @@ pub fn parse_parser_struct(input: &mut Iter) -> Result<(), Error> {
                 Some('+') => {
                     p.label = S6;
                 }
-                Some(c@')') => match p.peek() {
+                Some(c@')') => match p.peek(&[SL0, SL5]) {
                     SL5 => {
-                        let _ = p.pop(); // 5
-                        outprod("F = ( E )");
-                        match p.peek() {
+                        p.pop(); // 5
+                        actions.outprod("F = ( E )");
+                        match p.peek(&[SL0, SL5, SL6, SL7]) {
                             SL7 => p.label = S10,
                             _ => {
-                                outprod("T = F");
-                                match p.peek() {
+                                actions.outprod("T = F");
+                                match p.peek(&[SL0, SL5, SL6]) {
                                     SL6 => p.label = S9,
                                     _ => p.label = S2,
                                 }
@@ -116,38 +124,44 @@ pub fn parse_parser_struct(input: &mut Iter) -> Result<(), Error> {
                     _ => return Err(Error::Unexpected(c)),
                 },
                 Some(c) => return Err(Error::Unexpected(c)),
-                None => match p.peek() {
+                None => match p.peek(&[SL0, SL5]) {
                     SL5 => return Err(Error::EOF),
                     _ => {
-                        outprod("S = E");
+                        actions.outprod("S = E");
                         return Ok(());
                     }
                 },
             },
             TGoto |
-            S1 | S3 | S4 | S8 | S11 | FGoto => unsafe { unreachable_unchecked() },
+            S1 | S3 | S4 | S8 | S11 | FGoto => unsafe { unreachable_label(&p.label, &p) },
         }
     }
 }
 
 /// Make a single match out of it instead of two
-pub fn parse_single_match(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_single_match<A: Actions, S: ParseStack<Value = StackLabel>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
     use StackLabel::*;
 
-    let mut p = Parser::default();
+    let limit = DepthLimit::new(max_depth, 1, input);
+    let mut p = Parser::<S>::default();
     loop {
-        match (p.label, input.peek()) {
+        step(actions, &p.label, &p, input);
+        match (p.label, peek(input)) {
             (S0, Some('a')) => {
                 let _ = input.next();
-                p.push(SL0);
-                outprod("F = a");
-                outprod("T = F");
+                p.push(SL0, &limit, input)?;
+                actions.outprod("F = a");
+                actions.outprod("T = F");
                 p.label = S2;
             },
             (S0, Some('(')) => {
                 let _ = input.next();
-                p.push(SL0);
+                p.push(SL0, &limit, input)?;
                 p.label = S5;
             },
             (S0, Some(&c)) => return Err(Error::Unexpected(c)),
@@ -157,45 +171,46 @@ pub fn parse_single_match(input: &mut Iter) -> Result<(), Error> {
                 p.label = S7;
             },
             (S2, _) => {
-                outprod("E = T");
+                actions.outprod("E = T");
                 p.label = EGoto
             },
             (S5, Some('a')) => {
                 let _ = input.next();
-                p.push(SL5);
-                outprod("F = a");
-                outprod("T = F");
+                p.push(SL5, &limit, input)?;
+                actions.outprod("F = a");
+                actions.outprod("T = F");
                 p.label = S2;
             }
             (S5, Some('(')) => {
                 let _ = input.next();
-                p.push(SL5);
+                p.push(SL5, &limit, input)?;
                 p.label = S5; // (self)
             }
             (S5, Some(&c)) => return Err(Error::Unexpected(c)),
             (S5, None) => return Err(Error::EOF),
             (S6, Some('a')) => {
                 let _ = input.next();
-                p.push(SL6);
-                outprod("F = a");
+                p.push(SL6, &limit, input)?;
+                actions.outprod("F = a");
+                actions.outprod("T = F");
                 p.label = S9;
             }
             (S6, Some('(')) => {
                 let _ = input.next();
-                p.push(SL6);
+                p.push(SL6, &limit, input)?;
                 p.label = S5;
             }
             (S6, Some(&c)) => return Err(Error::Unexpected(c)),
             (S6, None) => return Err(Error::EOF),
             (S7, Some('a')) => {
                 let _ = input.next();
-                p.push(SL7);
-                outprod("F = a");
+                p.push(SL7, &limit, input)?;
+                actions.outprod("F = a");
                 p.label = S10
             }
             (S7, Some('(')) => {
                 let _ = input.next();
-                p.push(SL7);
+                p.push(SL7, &limit, input)?;
                 p.label = S5;
             }
             (S7, Some(&c)) => return Err(Error::Unexpected(c)),
@@ -205,14 +220,14 @@ pub fn parse_single_match(input: &mut Iter) -> Result<(), Error> {
                 p.label = S7;
             }
             (S9, _) => {
-                let _ = p.pop(); // 6
-                outprod("E = E + T");
+                p.pop(); // 6
+                actions.outprod("E = E + T");
                 p.label = EGoto
             }
             (S10, _) => {
-                let _ = p.pop(); // 7
-                outprod("T = T * F");
-                match p.peek() {
+                p.pop(); // 7
+                actions.outprod("T = T * F");
+                match p.peek(&[SL0, SL5, SL6]) {
                     SL6 => p.label = S9,
                     _ => p.label = S2,
                 }
@@ -221,16 +236,16 @@ pub fn parse_single_match(input: &mut Iter) -> Result<(), Error> {
                 let _ = input.next();
                 p.label = S6;
             }
-            (EGoto, Some(&c@')')) => match p.peek() {
+            (EGoto, Some(&c@')')) => match p.peek(&[SL0, SL5]) {
                 SL5 => {
                     let _ = input.next();
-                    let _ = p.pop(); // 5
-                    outprod("F = ( E )");
-                    match p.peek() {
+                    p.pop(); // 5
+                    actions.outprod("F = ( E )");
+                    match p.peek(&[SL0, SL5, SL6, SL7]) {
                         SL7 => p.label = S10,
                         _ => {
-                            outprod("T = F");
-                            match p.peek() {
+                            actions.outprod("T = F");
+                            match p.peek(&[SL0, SL5, SL6]) {
                                 SL6 => p.label = S9,
                                 _ => p.label = S2,
                             }
@@ -240,14 +255,14 @@ pub fn parse_single_match(input: &mut Iter) -> Result<(), Error> {
                 _ => return Err(Error::Unexpected(c)),
             },
             (EGoto, Some(&c)) => return Err(Error::Unexpected(c)),
-            (EGoto, None) => match p.peek() {
+            (EGoto, None) => match p.peek(&[SL0, SL5]) {
                 SL5 => return Err(Error::EOF),
                 _ => {
-                    outprod("S = E");
+                    actions.outprod("S = E");
                     return Ok(());
                 }
             },
-            _ => unsafe { unreachable_unchecked() },
+            _ => unsafe { unreachable_label(&p.label, &p) },
         }
     }
 }
@@ -1,22 +1,31 @@
-use crate::{outprod, Error, Iter, State, StackLabel, Parser};
-use std::hint::unreachable_unchecked;
+use crate::{Parser, StackLabel};
+use recursive_ascent_core::stack::ParseStack;
+use recursive_ascent_core::{
+    peek, step, top, unreachable_label, Actions, DepthLimit, Error, Iter, State,
+};
 
 /// Note the Goto is now labeled with the sort, and checks the stack for the state
 ///   (instead of the other way)
-pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_reverse_goto<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
 
-    let mut stack = vec![];
+    let limit = DepthLimit::new(max_depth, 0, input);
+    let mut stack = S::from_slice(&[]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -24,41 +33,41 @@ pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
             },
             S1 => match input.next() {
                 Some('+') => {
-                    stack.push(1);
+                    limit.push(&mut stack, 1, input)?;
                     label = S6;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => {
-                    outprod("S = E");
+                    actions.outprod("S = E");
                     return Ok(());
                 }
             },
-            S2 => match input.peek() {
+            S2 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
-                    stack.push(2);
+                    limit.push(&mut stack, 2, input)?;
                     label = S7;
                 }
                 _ => {
-                    outprod("E = T");
+                    actions.outprod("E = T");
                     label = EGoto
                 }
             },
             S3 => {
-                outprod("T = F");
+                actions.outprod("T = F");
                 label = TGoto
             }
             S4 => {
-                outprod("F = a");
+                actions.outprod("F = a");
                 label = FGoto
             }
             S5 => match input.next() {
                 Some('a') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S5; // (self)
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -66,11 +75,11 @@ pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
             },
             S6 => match input.next() {
                 Some('a') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -78,11 +87,11 @@ pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
             },
             S7 => match input.next() {
                 Some('a') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -90,50 +99,50 @@ pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
             },
             S8 => match input.next() {
                 Some('+') => {
-                    stack.push(8);
+                    limit.push(&mut stack, 8, input)?;
                     label = S6;
                 }
                 Some(')') => {
-                    stack.push(8);
+                    limit.push(&mut stack, 8, input)?;
                     label = S11;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S9 => match input.peek() {
+            S9 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
-                    stack.push(9);
+                    limit.push(&mut stack, 9, input)?;
                     label = S7;
                 }
                 _ => {
                     let _ = stack.pop(); // 6
                     let _ = stack.pop(); // 1
-                    outprod("E = E + T");
+                    actions.outprod("E = E + T");
                     label = EGoto
                 }
             },
             S10 => {
                 let _ = stack.pop(); // 7
                 let _ = stack.pop(); // 2 or 9
-                outprod("T = T * F");
+                actions.outprod("T = T * F");
                 label = TGoto
             }
             S11 => {
                 let _ = stack.pop(); // 8
                 let _ = stack.pop(); // 5
-                outprod("F = ( E )");
+                actions.outprod("F = ( E )");
                 label = FGoto
             }
-            EGoto => match stack[stack.len() - 1] {
+            EGoto => match top(&label, &stack, &[0, 5]) {
                 5 => label = S8,
                 _ => label = S1,
             },
-            TGoto => match stack[stack.len() - 1] {
+            TGoto => match top(&label, &stack, &[0, 5, 6]) {
                 6 => label = S9,
                 _ => label = S2,
             },
-            FGoto => match stack[stack.len() - 1] {
+            FGoto => match top(&label, &stack, &[0, 5, 6, 7]) {
                 7 => label = S10,
                 _ => label = S3,
             },
@@ -143,20 +152,26 @@ pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
 
 /// We're starting with reversed goto again, that seems nice. But now we do ascent-descent, where
 ///   we switch to LL when committing to a rule. This inlines S10 and S11 instead of S3 and S4.
-pub fn parse_asc_desc(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_asc_desc<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
 
-    let mut stack = vec![];
+    let limit = DepthLimit::new(max_depth, 0, input);
+    let mut stack = S::from_slice(&[]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -164,41 +179,41 @@ pub fn parse_asc_desc(input: &mut Iter) -> Result<(), Error> {
             },
             S1 => match input.next() {
                 Some('+') => {
-                    stack.push(1);
+                    limit.push(&mut stack, 1, input)?;
                     label = S6;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => {
-                    outprod("S = E");
+                    actions.outprod("S = E");
                     return Ok(());
                 }
             },
-            S2 => match input.peek() {
+            S2 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
-                    stack.push(2);
+                    limit.push(&mut stack, 2, input)?;
                     label = S7;
                 }
                 _ => {
-                    outprod("E = T");
+                    actions.outprod("E = T");
                     label = EGoto
                 }
             },
             S3 => {
-                outprod("T = F");
+                actions.outprod("T = F");
                 label = TGoto
             }
             S4 => {
-                outprod("F = a");
+                actions.outprod("F = a");
                 label = FGoto
             }
             S5 => match input.next() {
                 Some('a') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S5; // (self)
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -206,11 +221,11 @@ pub fn parse_asc_desc(input: &mut Iter) -> Result<(), Error> {
             },
             S6 => match input.next() {
                 Some('a') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -218,11 +233,11 @@ pub fn parse_asc_desc(input: &mut Iter) -> Result<(), Error> {
             },
             S7 => match input.next() {
                 Some('a') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -230,48 +245,48 @@ pub fn parse_asc_desc(input: &mut Iter) -> Result<(), Error> {
             },
             S8 => match input.next() {
                 Some('+') => {
-                    stack.push(8);
+                    limit.push(&mut stack, 8, input)?;
                     label = S6;
                 }
                 Some(')') => {
                     let _ = stack.pop(); // 5
-                    outprod("F = ( E )");
+                    actions.outprod("F = ( E )");
                     label = FGoto
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S9 => match input.peek() {
+            S9 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
-                    stack.push(9);
+                    limit.push(&mut stack, 9, input)?;
                     label = S7;
                 }
                 _ => {
                     let _ = stack.pop(); // 6
                     let _ = stack.pop(); // 1
-                    outprod("E = E + T");
+                    actions.outprod("E = E + T");
                     label = EGoto
                 }
             },
-            EGoto => match stack[stack.len() - 1] {
+            EGoto => match top(&label, &stack, &[0, 5]) {
                 5 => label = S8,
                 _ => label = S1,
             },
-            TGoto => match stack[stack.len() - 1] {
+            TGoto => match top(&label, &stack, &[0, 5, 6]) {
                 6 => label = S9,
                 _ => label = S2,
             },
-            FGoto => match stack[stack.len() - 1] {
+            FGoto => match top(&label, &stack, &[0, 5, 6, 7]) {
                 7 => {
                     let _ = stack.pop(); // 7
                     let _ = stack.pop(); // 2 or 9
-                    outprod("T = T * F");
+                    actions.outprod("T = T * F");
                     label = TGoto
                 }
                 _ => label = S3,
             },
-            S10 | S11 => unsafe { unreachable_unchecked() },
+            S10 | S11 => unsafe { unreachable_label(&label, &stack) },
         }
     }
 }
@@ -281,20 +296,26 @@ pub fn parse_asc_desc(input: &mut Iter) -> Result<(), Error> {
 ///   inlining of states 10/11, you've lost the static information on where you are and cannot
 ///   inline the Goto label. But we did get rid of S5/S6/S7. So which one is better? Probably the
 ///   one that pushes late, because it pushes less... Pushing less? We know a trick for that.
-pub fn parse_push_first(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_push_first<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
 
-    let mut stack = vec![0];
+    let limit = DepthLimit::new(max_depth, 1, input);
+    let mut stack = S::from_slice(&[0]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    stack.push(4);
+                    limit.push(&mut stack, 4, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S0;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -302,62 +323,62 @@ pub fn parse_push_first(input: &mut Iter) -> Result<(), Error> {
             },
             S1 => match input.next() {
                 Some('+') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S0;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => {
-                    outprod("S = E");
+                    actions.outprod("S = E");
                     return Ok(());
                 }
             },
-            S2 => match input.peek() {
+            S2 => match peek(input) {
                 Some('*') => {
                     let _ = input.next(); // *
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S0;
                 }
                 _ => {
                     let _ = stack.pop(); // 2
-                    outprod("E = T");
+                    actions.outprod("E = T");
                     label = EGoto
                 }
             },
             S3 => {
                 let _ = stack.pop(); // 3
-                outprod("T = F");
+                actions.outprod("T = F");
                 label = TGoto
             }
             S4 => {
                 let _ = stack.pop(); // 4
-                outprod("F = a");
+                actions.outprod("F = a");
                 label = FGoto
             }
             S8 => match input.next() {
                 Some('+') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S0;
                 }
                 Some(')') => {
                     let _ = stack.pop(); // 8
                     let _ = stack.pop(); // 5
-                    outprod("F = ( E )");
+                    actions.outprod("F = ( E )");
                     label = FGoto
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S9 => match input.peek() {
+            S9 => match peek(input) {
                 Some('*') => {
                     let _ = input.next(); // *
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S0;
                 }
                 _ => {
                     let _ = stack.pop(); // 9
                     let _ = stack.pop(); // 6
                     let _ = stack.pop(); // 1
-                    outprod("E = E + T");
+                    actions.outprod("E = E + T");
                     label = EGoto
                 }
             },
@@ -365,69 +386,75 @@ pub fn parse_push_first(input: &mut Iter) -> Result<(), Error> {
                 let _ = stack.pop(); // 10
                 let _ = stack.pop(); // 7
                 let _ = stack.pop(); // 2 or 9
-                outprod("T = T * F");
+                actions.outprod("T = T * F");
                 label = TGoto
             },
             S11 => {
                 let _ = stack.pop(); // 11
                 let _ = stack.pop(); // 8
                 let _ = stack.pop(); // 5
-                outprod("F = ( E )");
+                actions.outprod("F = ( E )");
                 label = FGoto
             },
             EGoto => {
-                match stack[stack.len() - 1] {
+                match top(&label, &stack, &[0, 5]) {
                     5 => {
-                        stack.push(8);
+                        limit.push_goto(&mut stack, 8, input)?;
                         label = S8
                     }
                     // 0
                     _ => {
-                        stack.push(1);
+                        limit.push_goto(&mut stack, 1, input)?;
                         label = S1
                     }
                 }
             }
-            TGoto => match stack[stack.len() - 1] {
+            TGoto => match top(&label, &stack, &[0, 5, 6]) {
                 6 => {
-                    stack.push(9);
+                    limit.push_goto(&mut stack, 9, input)?;
                     label = S9
                 }
                 _ => {
-                    stack.push(2);
+                    limit.push_goto(&mut stack, 2, input)?;
                     label = S2
                 }
             },
-            FGoto => match stack[stack.len() - 1] {
+            FGoto => match top(&label, &stack, &[0, 5, 6, 7]) {
                 7 => {
-                    stack.push(10);
+                    limit.push_goto(&mut stack, 10, input)?;
                     label = S10
                 }
                 _ => {
-                    stack.push(3);
+                    limit.push_goto(&mut stack, 3, input)?;
                     label = S3
                 }
             },
-            S5 | S6 | S7 => unsafe { unreachable_unchecked() },
+            S5 | S6 | S7 => unsafe { unreachable_label(&label, &stack) },
         }
     }
 }
 
 /// So we continue from push_first with a minpush approach now: push 0/5/6/7. This leaves us once
 ///   more with minimal pushing to the stack.
-pub fn parse_minpush(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_minpush<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
 
-    let mut stack = vec![0];
+    let limit = DepthLimit::new(max_depth, 1, input);
+    let mut stack = S::from_slice(&[0]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S0;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -435,121 +462,127 @@ pub fn parse_minpush(input: &mut Iter) -> Result<(), Error> {
             },
             S1 => match input.next() {
                 Some('+') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S0;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => {
-                    outprod("S = E");
+                    actions.outprod("S = E");
                     return Ok(());
                 }
             },
-            S2 => match input.peek() {
+            S2 => match peek(input) {
                 Some('*') => {
                     let _ = input.next(); // *
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S0;
                 }
                 _ => {
-                    outprod("E = T");
+                    actions.outprod("E = T");
                     label = EGoto
                 }
             },
             S3 => {
-                outprod("T = F");
+                actions.outprod("T = F");
                 label = TGoto
             }
             S4 => {
-                outprod("F = a");
+                actions.outprod("F = a");
                 label = FGoto
             }
             S8 => match input.next() {
                 Some('+') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S0;
                 }
                 Some(')') => {
                     let _ = stack.pop(); // 5
-                    outprod("F = ( E )");
+                    actions.outprod("F = ( E )");
                     label = FGoto
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S9 => match input.peek() {
+            S9 => match peek(input) {
                 Some('*') => {
                     let _ = input.next(); // *
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S0;
                 }
                 _ => {
                     let _ = stack.pop(); // 6
-                    outprod("E = E + T");
+                    actions.outprod("E = E + T");
                     label = EGoto
                 }
             },
             S10 => {
                 let _ = stack.pop(); // 7
-                outprod("T = T * F");
+                actions.outprod("T = T * F");
                 label = TGoto
             },
             S11 => {
                 let _ = stack.pop(); // 5
-                outprod("F = ( E )");
+                actions.outprod("F = ( E )");
                 label = FGoto
             },
             EGoto => {
-                match stack[stack.len() - 1] {
+                match top(&label, &stack, &[0, 5]) {
                     5 => label = S8,
                     // 0
                     _ => label = S1,
                 }
             }
-            TGoto => match stack[stack.len() - 1] {
+            TGoto => match top(&label, &stack, &[0, 5, 6]) {
                 6 => label = S9,
                 _ => label = S2,
             },
-            FGoto => match stack[stack.len() - 1] {
+            FGoto => match top(&label, &stack, &[0, 5, 6, 7]) {
                 7 => label = S10,
                 _ => label = S3,
             },
-            S5 | S6 | S7 => unsafe { unreachable_unchecked() },
+            S5 | S6 | S7 => unsafe { unreachable_label(&label, &stack) },
         }
     }
 }
 
 /// Now with nothing left to do, we inline every label used in only one place. We go from 3 unused
 ///   labels to 11, only 4 labels left in use!
-pub fn parse_inline1(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_inline1<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
 
-    let mut stack = vec![0];
+    let limit = DepthLimit::new(max_depth, 1, input);
+    let mut stack = S::from_slice(&[0]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    outprod("F = a");
+                    actions.outprod("F = a");
                     label = FGoto
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S0;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
             EGoto => {
-                match stack[stack.len() - 1] {
+                match top(&label, &stack, &[0, 5]) {
                     5 => {
                         match input.next() {
                             Some('+') => {
-                                stack.push(6);
+                                limit.push(&mut stack, 6, input)?;
                                 label = S0;
                             }
                             Some(')') => {
                                 let _ = stack.pop(); // 5
-                                outprod("F = ( E )");
+                                actions.outprod("F = ( E )");
                                 label = FGoto
                             }
                             Some(c) => return Err(Error::Unexpected(c)),
@@ -560,56 +593,56 @@ pub fn parse_inline1(input: &mut Iter) -> Result<(), Error> {
                         // assert!(stack[stack.len() - 1] == 0)
                         match input.next() {
                             Some('+') => {
-                                stack.push(6);
+                                limit.push(&mut stack, 6, input)?;
                                 label = S0;
                             }
                             Some(c) => return Err(Error::Unexpected(c)),
                             None => {
-                                outprod("S = E");
+                                actions.outprod("S = E");
                                 return Ok(());
                             }
                         }
                     }
                 }
             }
-            TGoto => match stack[stack.len() - 1] {
-                6 => match input.peek() {
+            TGoto => match top(&label, &stack, &[0, 5, 6]) {
+                6 => match peek(input) {
                     Some('*') => {
                         let _ = input.next(); // *
-                        stack.push(7);
+                        limit.push(&mut stack, 7, input)?;
                         label = S0;
                     }
                     _ => {
                         let _ = stack.pop(); // 6
-                        outprod("E = E + T");
+                        actions.outprod("E = E + T");
                         label = EGoto
                     }
                 },
-                _ => match input.peek() {
+                _ => match peek(input) {
                     Some('*') => {
                         let _ = input.next(); // *
-                        stack.push(7);
+                        limit.push(&mut stack, 7, input)?;
                         label = S0;
                     }
                     _ => {
-                        outprod("E = T");
+                        actions.outprod("E = T");
                         label = EGoto
                     }
                 },
             },
-            FGoto => match stack[stack.len() - 1] {
+            FGoto => match top(&label, &stack, &[0, 5, 6, 7]) {
                 7 => {
                     let _ = stack.pop(); // 7
-                    outprod("T = T * F");
+                    actions.outprod("T = T * F");
                     label = TGoto
                 }
                 _ => {
-                    outprod("T = F");
+                    actions.outprod("T = F");
                     label = TGoto
                 }
             },
             S1 | S2 | S3 | S4 | S5 | S6 | S7 | S8 | S9 | S10 | S11 => unsafe {
-                unreachable_unchecked()
+                unreachable_label(&label, &stack)
             },
         }
     }
@@ -619,61 +652,67 @@ pub fn parse_inline1(input: &mut Iter) -> Result<(), Error> {
 ///   labels used in only one place, and go down to two labels.
 /// If you're willing to duplicate the semantic actions (calls to outprod are placeholders for
 ///   these), you can do a single match on the `input.next()` result. Probably not worth it.
-pub fn parse_inline2(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_inline2<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
 
-    let mut stack = vec![0];
+    let limit = DepthLimit::new(max_depth, 1, input);
+    let mut stack = S::from_slice(&[0]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    outprod("F = a");
+                    actions.outprod("F = a");
                     label = FGoto
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S0;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
             FGoto => {
-                match stack[stack.len() - 1] {
+                match top(&label, &stack, &[0, 5, 6, 7]) {
                     7 => {
                         let _ = stack.pop(); // 7
-                        outprod("T = T * F");
+                        actions.outprod("T = T * F");
                     }
                     _ => {
-                        outprod("T = F");
+                        actions.outprod("T = F");
                     }
                 }
-                match input.peek() {
+                match peek(input) {
                     Some('*') => {
                         let _ = input.next(); // *
-                        stack.push(7);
+                        limit.push(&mut stack, 7, input)?;
                         label = S0;
                     }
                     _ => {
-                        match stack[stack.len() - 1] {
+                        match top(&label, &stack, &[0, 5, 6]) {
                             6 => {
                                 let _ = stack.pop(); // 6
-                                outprod("E = E + T");
+                                actions.outprod("E = E + T");
                             }
                             _ => {
-                                outprod("E = T");
+                                actions.outprod("E = T");
                             }
                         }
-                        match stack[stack.len() - 1] {
+                        match top(&label, &stack, &[0, 5]) {
                             5 => {
                                 match input.next() {
                                     Some('+') => {
-                                        stack.push(6);
+                                        limit.push(&mut stack, 6, input)?;
                                         label = S0;
                                     }
                                     Some(')') => {
                                         let _ = stack.pop(); // 5
-                                        outprod("F = ( E )");
+                                        actions.outprod("F = ( E )");
                                         label = FGoto // (self)
                                     }
                                     Some(c) => return Err(Error::Unexpected(c)),
@@ -684,12 +723,12 @@ pub fn parse_inline2(input: &mut Iter) -> Result<(), Error> {
                                 // assert!(stack[stack.len() - 1] == 0)
                                 match input.next() {
                                     Some('+') => {
-                                        stack.push(6);
+                                        limit.push(&mut stack, 6, input)?;
                                         label = S0;
                                     }
                                     Some(c) => return Err(Error::Unexpected(c)),
                                     None => {
-                                        outprod("S = E");
+                                        actions.outprod("S = E");
                                         return Ok(());
                                     }
                                 }
@@ -699,67 +738,73 @@ pub fn parse_inline2(input: &mut Iter) -> Result<(), Error> {
                 }
             }
             S1 | S2 | S3 | S4 | S5 | S6 | S7 | S8 | S9 | S10 | S11 | EGoto | TGoto => unsafe {
-                unreachable_unchecked()
+                unreachable_label(&label, &stack)
             },
         }
     }
 }
 
-pub fn parse_single_input_next1(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_single_input_next1<A: Actions, S: ParseStack<Value = StackLabel>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use StackLabel::*;
     use State::*;
 
-    let mut stack = vec![SL0];
+    let limit = DepthLimit::new(max_depth, 1, input);
+    let mut stack = S::from_slice(&[SL0]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    outprod("F = a");
+                    actions.outprod("F = a");
                     label = FGoto
                 }
                 Some('(') => {
-                    stack.push(SL5);
+                    limit.push(&mut stack, SL5, input)?;
                     label = S0;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
             FGoto => {
-                match stack[stack.len() - 1] {
+                match top(&label, &stack, &[SL0, SL5, SL6, SL7]) {
                     SL7 => {
                         let _ = stack.pop(); // 7
-                        outprod("T = T * F");
+                        actions.outprod("T = T * F");
                     }
                     _ => {
-                        outprod("T = F");
+                        actions.outprod("T = F");
                     }
                 }
                 match input.next() {
                     Some('*') => {
-                        stack.push(SL7);
+                        limit.push(&mut stack, SL7, input)?;
                         label = S0;
                     }
                     i => {
-                        match stack[stack.len() - 1] {
+                        match top(&label, &stack, &[SL0, SL5, SL6]) {
                             SL6 => {
                                 let _ = stack.pop(); // 6
-                                outprod("E = E + T");
+                                actions.outprod("E = E + T");
                             }
                             _ => {
-                                outprod("E = T");
+                                actions.outprod("E = T");
                             }
                         }
                         match i {
                             Some('+') => {
-                                stack.push(SL6);
+                                limit.push(&mut stack, SL6, input)?;
                                 label = S0;
                             }
                             Some(c @ ')') => {
-                                match stack[stack.len() - 1] {
+                                match top(&label, &stack, &[SL0, SL5]) {
                                     SL5 => {
                                         let _ = stack.pop(); // 5
-                                        outprod("F = ( E )");
+                                        actions.outprod("F = ( E )");
                                         label = FGoto // (self)
                                     }
                                     _ => return Err(Error::Unexpected(c)),
@@ -767,10 +812,10 @@ pub fn parse_single_input_next1(input: &mut Iter) -> Result<(), Error> {
                             }
                             Some(c) => return Err(Error::Unexpected(c)),
                             None => {
-                                return match stack[stack.len() - 1] {
+                                return match top(&label, &stack, &[SL0, SL5]) {
                                     SL5 => Err(Error::EOF),
                                     _ => {
-                                        outprod("S = E");
+                                        actions.outprod("S = E");
                                         Ok(())
                                     }
                                 }
@@ -780,70 +825,76 @@ pub fn parse_single_input_next1(input: &mut Iter) -> Result<(), Error> {
                 }
             }
             S1 | S2 | S3 | S4 | S5 | S6 | S7 | S8 | S9 | S10 | S11 | EGoto | TGoto => unsafe {
-                unreachable_unchecked()
+                unreachable_label(&label, &stack)
             },
         }
     }
 }
 
-pub fn parse_single_input_next(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_single_input_next<A: Actions, S: ParseStack<Value = StackLabel>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use StackLabel::*;
     use State::*;
 
-    let mut p = Parser::default();
+    let limit = DepthLimit::new(max_depth, 1, input);
+    let mut p = Parser::<S>::default();
 
     loop {
+        step(actions, &p.label, &p, input);
         match (p.label, input.next()) {
             (S0, Some('a')) => {
-                outprod("F = a");
+                actions.outprod("F = a");
                 p.label = FGoto
             }
             (S0, Some('(')) => {
-                p.push(SL5);
+                p.push(SL5, &limit, input)?;
                 // p.label = S0 // (self)
             }
             (S0, Some(c)) => return Err(Error::Unexpected(c)),
             (S0, None) => return Err(Error::EOF),
             (FGoto, Some('*')) => {
-                if let SL7 = p.peek() {
-                    outprod("T = T * F");
+                if let SL7 = p.peek(&[SL0, SL5, SL6, SL7]) {
+                    actions.outprod("T = T * F");
                 } else {
-                    outprod("T = F");
-                    p.push(SL7);
+                    actions.outprod("T = F");
+                    p.push(SL7, &limit, input)?;
                 }
                 p.label = S0
             }
             (FGoto, Some('+')) => {
-                if let SL7 = p.peek() {
+                if let SL7 = p.peek(&[SL0, SL5, SL6, SL7]) {
                     p.pop(); // 7
-                    outprod("T = T * F");
+                    actions.outprod("T = T * F");
                 } else {
-                    outprod("T = F");
+                    actions.outprod("T = F");
                 }
-                if let SL6 = p.peek() {
-                    outprod("E = E + T");
+                if let SL6 = p.peek(&[SL0, SL5, SL6]) {
+                    actions.outprod("E = E + T");
                 } else {
-                    outprod("E = T");
-                    p.push(SL6);
+                    actions.outprod("E = T");
+                    p.push(SL6, &limit, input)?;
                 }
                 p.label = S0;
             }
             (FGoto, Some(c @ ')')) => {
-                if let SL7 = p.peek() {
+                if let SL7 = p.peek(&[SL0, SL5, SL6, SL7]) {
                     p.pop(); // 7
-                    outprod("T = T * F");
+                    actions.outprod("T = T * F");
                 } else {
-                    outprod("T = F");
+                    actions.outprod("T = F");
                 }
-                if let SL6 = p.peek() {
+                if let SL6 = p.peek(&[SL0, SL5, SL6]) {
                     p.pop(); // 6
-                    outprod("E = E + T");
+                    actions.outprod("E = E + T");
                 } else {
-                    outprod("E = T");
+                    actions.outprod("E = T");
                 }
-                if let SL5 = p.peek() {
+                if let SL5 = p.peek(&[SL0, SL5]) {
                     p.pop(); // 5
-                    outprod("F = ( E )");
+                    actions.outprod("F = ( E )");
                     // p.label = FGoto // (self)
                 } else {
                     return Err(Error::Unexpected(c));
@@ -851,26 +902,26 @@ pub fn parse_single_input_next(input: &mut Iter) -> Result<(), Error> {
             }
             (FGoto, Some(c)) => return Err(Error::Unexpected(c)),
             (FGoto, None) => {
-                if let SL7 = p.peek() {
+                if let SL7 = p.peek(&[SL0, SL5, SL6, SL7]) {
                     p.pop(); // 7
-                    outprod("T = T * F");
+                    actions.outprod("T = T * F");
                 } else {
-                    outprod("T = F");
+                    actions.outprod("T = F");
                 }
-                if let SL6 = p.peek() {
+                if let SL6 = p.peek(&[SL0, SL5, SL6]) {
                     p.pop(); // 6
-                    outprod("E = E + T");
+                    actions.outprod("E = E + T");
                 } else {
-                    outprod("E = T");
+                    actions.outprod("E = T");
                 }
-                return if let SL5 = p.peek() {
+                return if let SL5 = p.peek(&[SL0, SL5]) {
                     Err(Error::EOF)
                 } else {
-                    outprod("S = E");
+                    actions.outprod("S = E");
                     Ok(())
                 };
             }
-            _ => unsafe { unreachable_unchecked() },
+            _ => unsafe { unreachable_label(&p.label, &p) },
         }
     }
 }
@@ -0,0 +1,203 @@
+//! More than one expression from one input. The variants only accept at the end of the input, so
+//!   each call parses exactly one expression. Here the parser accepts as soon as the expression
+//!   cannot continue, which gives the longest prefix that is an expression, and a sequence of
+//!   expressions separated by `;` or newlines.
+
+use crate::{Parser, StackLabel};
+use recursive_ascent_core::stack::ParseStack;
+use recursive_ascent_core::{
+    peek, step, unreachable_label, Actions, DepthLimit, Error, Iter, State,
+};
+use std::marker::PhantomData;
+
+/// [`crate::paper::parse_single_match`] with the parser passed in, that accepts at the first token
+///   that cannot continue the expression and leaves that token in the input. With one token of
+///   lookahead the parser cannot go back, so `a+` is an error here, see [`parse_longest_prefix`].
+pub fn parse_prefix<A: Actions, S: ParseStack<Value = StackLabel>>(
+    p: &mut Parser<S>,
+    input: &mut Iter,
+    actions: &mut A,
+    limit: &DepthLimit,
+) -> Result<(), Error> {
+    use StackLabel::*;
+    use State::*;
+
+    loop {
+        step(actions, &p.label, p, input);
+        match (p.label, peek(input)) {
+            (S0 | S5 | S6 | S7, Some('a')) => {
+                let _ = input.next();
+                p.push(stack_label(p.label), limit, input)?;
+                actions.outprod("F = a");
+                p.label = match p.label {
+                    S7 => S10,
+                    label => {
+                        actions.outprod("T = F");
+                        if label == S6 {
+                            S9
+                        } else {
+                            S2
+                        }
+                    }
+                };
+            }
+            (S0 | S5 | S6 | S7, Some('(')) => {
+                let _ = input.next();
+                p.push(stack_label(p.label), limit, input)?;
+                p.label = S5;
+            }
+            (S0 | S5 | S6 | S7, Some(&c)) => return Err(Error::Unexpected(c)),
+            (S0 | S5 | S6 | S7, None) => return Err(Error::EOF),
+            (S2 | S9, Some('*')) => {
+                let _ = input.next();
+                p.label = S7;
+            }
+            (S2, _) => {
+                actions.outprod("E = T");
+                p.label = EGoto;
+            }
+            (S9, _) => {
+                p.pop(); // 6
+                actions.outprod("E = E + T");
+                p.label = EGoto;
+            }
+            (S10, _) => {
+                p.pop(); // 7
+                actions.outprod("T = T * F");
+                p.label = match p.peek(&[SL0, SL5, SL6]) {
+                    SL6 => S9,
+                    _ => S2,
+                };
+            }
+            (EGoto, Some('+')) => {
+                let _ = input.next();
+                p.label = S6;
+            }
+            (EGoto, lookahead) => match (p.peek(&[SL0, SL5]), lookahead) {
+                (SL5, Some(')')) => {
+                    let _ = input.next();
+                    p.pop(); // 5
+                    actions.outprod("F = ( E )");
+                    p.label = match p.peek(&[SL0, SL5, SL6, SL7]) {
+                        SL7 => S10,
+                        top => {
+                            actions.outprod("T = F");
+                            if top == SL6 {
+                                S9
+                            } else {
+                                S2
+                            }
+                        }
+                    };
+                }
+                (SL5, Some(&c)) => return Err(Error::Unexpected(c)),
+                (SL5, None) => return Err(Error::EOF),
+                // the end of the expression, whatever comes next
+                _ => {
+                    actions.outprod("S = E");
+                    return Ok(());
+                }
+            },
+            _ => unsafe { unreachable_label(&p.label, p) },
+        }
+    }
+}
+
+/// The label a shift from `label` pushes, so the goto after the reduction gets back there
+#[inline(always)]
+fn stack_label(label: State) -> StackLabel {
+    match label {
+        State::S5 => StackLabel::SL5,
+        State::S6 => StackLabel::SL6,
+        State::S7 => StackLabel::SL7,
+        _ => StackLabel::SL0,
+    }
+}
+
+/// Parses the longest prefix of `input` that is an expression and returns the rest of the input.
+///   [`parse_prefix`] cannot go back, so a first run without actions finds where the prefix ends,
+///   and a second run parses just the prefix with `actions`.
+pub fn parse_longest_prefix<'a, A: Actions, S: ParseStack<Value = StackLabel>>(
+    input: &'a str,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<&'a str, Error> {
+    let mut iter = input.chars().peekable();
+    let limit = DepthLimit::new(max_depth, 1, &iter);
+    let probe = parse_prefix(&mut Parser::<S>::default(), &mut iter, &mut (), &limit);
+    let read = input.len() - iter.map(char::len_utf8).sum::<usize>();
+    let end = match probe {
+        Ok(()) => read,
+        Err(error @ Error::TooDeep { .. }) => return Err(error),
+        Err(error) => last_expression_end(&input[..read]).ok_or(error)?,
+    };
+    let mut iter = input[..end].chars().peekable();
+    let limit = DepthLimit::new(max_depth, 1, &iter);
+    parse_prefix(&mut Parser::<S>::default(), &mut iter, actions, &limit)?;
+    Ok(&input[end..])
+}
+
+/// Where the last expression in `read` ends, if any. The parser got through `read`, so every prefix
+///   of it that ends with an `a` or a `)` and closes all its `(` is an expression.
+fn last_expression_end(read: &str) -> Option<usize> {
+    let mut open = 0;
+    let mut end = None;
+    for (i, c) in read.char_indices() {
+        match c {
+            '(' => open += 1,
+            ')' => open -= 1,
+            _ => {}
+        }
+        if open == 0 && (c == 'a' || c == ')') {
+            end = Some(i + c.len_utf8());
+        }
+    }
+    end
+}
+
+fn is_separator(c: char) -> bool {
+    c == ';' || c == '\n'
+}
+
+/// The expressions of an input, separated by `;` or newlines. Each expression is parsed with new
+///   actions `A`, which are yielded when the expression parses. Empty expressions are skipped, and
+///   after an error the rest of that expression is skipped. The parser and its stack are reused for
+///   every expression.
+pub struct Expressions<'a, A, S> {
+    parser: Parser<S>,
+    input: Iter<'a>,
+    limit: DepthLimit<'a>,
+    actions: PhantomData<fn() -> A>,
+}
+
+impl<'a, A, S: ParseStack<Value = StackLabel>> Expressions<'a, A, S> {
+    pub fn new(input: &'a str, max_depth: usize) -> Self {
+        let input = input.chars().peekable();
+        Expressions {
+            parser: Parser::default(),
+            limit: DepthLimit::new(max_depth, 1, &input),
+            input,
+            actions: PhantomData,
+        }
+    }
+}
+
+impl<A: Actions + Default, S: ParseStack<Value = StackLabel>> Iterator for Expressions<'_, A, S> {
+    type Item = Result<A, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.input.next_if(|&c| is_separator(c)).is_some() {}
+        self.input.peek()?;
+        self.parser.reset();
+        let mut actions = A::default();
+        let result = parse_prefix(&mut self.parser, &mut self.input, &mut actions, &self.limit)
+            .and_then(|()| match self.input.peek() {
+                Some(&c) if !is_separator(c) => Err(Error::Unexpected(c)),
+                _ => Ok(()),
+            });
+        if result.is_err() {
+            while self.input.next_if(|&c| !is_separator(c)).is_some() {}
+        }
+        Some(result.map(|()| actions))
+    }
+}
@@ -0,0 +1,493 @@
+use optimising_recursive_ascent_part_2::automaton::{self, Action, Automaton};
+use std::collections::{BTreeMap, BTreeSet};
+
+fn stages() -> Vec<(&'static str, Automaton)> {
+    vec![
+        ("lalr-automaton", automaton::lalr()),
+        ("pda-automaton", automaton::pda()),
+        ("reverse-goto", automaton::reverse_goto()),
+        ("push-first", automaton::push_first()),
+        ("minimal-push", automaton::minimal_push()),
+        ("max-inline-1", automaton::max_inline_1()),
+        ("max-inline-2", automaton::max_inline_2()),
+    ]
+}
+
+/// The actions on the edges and on the way into the start node
+fn actions(automaton: &Automaton) -> impl Iterator<Item = &Action> {
+    let edges = automaton
+        .edges
+        .iter()
+        .flat_map(|e| e.options.iter().flatten());
+    automaton.start_actions.iter().chain(edges)
+}
+
+/// How often each state number is pushed, popped and peeked at
+fn stack_actions(automaton: &Automaton) -> [BTreeMap<&str, usize>; 3] {
+    let mut counts = [BTreeMap::new(), BTreeMap::new(), BTreeMap::new()];
+    for action in actions(automaton) {
+        let (i, s) = match action {
+            Action::Push(s) => (0, s),
+            Action::Pop(s) => (1, s),
+            Action::Peek(s) => (2, s),
+            Action::Shift(_) => continue,
+        };
+        *counts[i].entry(s.as_str()).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn names(automaton: &Automaton) -> Vec<&str> {
+    automaton.nodes.iter().map(|n| n.name.as_str()).collect()
+}
+
+#[test]
+fn nodes_and_edges_of_each_stage() {
+    let sizes: Vec<(&str, usize, usize, usize)> = stages()
+        .iter()
+        .map(|(name, a)| {
+            let groups = (0..a.nodes.len()).filter(|&n| a.root(n) == n).count();
+            (*name, a.nodes.len(), a.edges.len(), groups)
+        })
+        .collect();
+    assert_eq!(
+        sizes,
+        [
+            ("lalr-automaton", 13, 23, 13),
+            ("pda-automaton", 17, 41, 17),
+            ("reverse-goto", 16, 26, 16),
+            ("push-first", 13, 20, 13),
+            ("minimal-push", 13, 20, 13),
+            ("max-inline-1", 13, 20, 5),
+            ("max-inline-2", 13, 20, 3),
+        ]
+    );
+}
+
+#[test]
+fn merged_nodes_are_named_after_their_parts() {
+    let push_first = automaton::push_first();
+    assert_eq!(
+        names(&push_first),
+        [
+            "S0/S5/S6/S7",
+            "S1",
+            "S2",
+            "S3",
+            "S4",
+            "S8",
+            "S9",
+            "S10",
+            "S11",
+            "EGoto",
+            "TGoto",
+            "FGoto",
+            "accept"
+        ]
+    );
+    assert_eq!(push_first.nodes[push_first.start].name, "S0/S5/S6/S7");
+    // the states are not merged before the state numbers that are pushed make them the same
+    assert!(names(&automaton::reverse_goto())
+        .iter()
+        .all(|n| !n.contains('/')));
+}
+
+#[test]
+fn the_lalr_automaton_only_shifts() {
+    let lalr = automaton::lalr();
+    assert!(actions(&lalr).all(|a| matches!(a, Action::Shift(_))));
+    assert_eq!(
+        stack_actions(&lalr),
+        [BTreeMap::new(), BTreeMap::new(), BTreeMap::new()]
+    );
+}
+
+/// Counts of `(state number or sort, times)`
+fn counts<'a>(counts: &[(&'a str, usize)]) -> BTreeMap<&'a str, usize> {
+    counts.iter().copied().collect()
+}
+
+#[test]
+fn push_and_pop_annotations_of_each_stage() {
+    // every path a reduction can take peeks at the state it goes back to and pushes the sort
+    let pda = automaton::pda();
+    let [pushed, popped, peeked] = stack_actions(&pda);
+    let shifts = [
+        ("0", 2),
+        ("1", 1),
+        ("2", 1),
+        ("5", 2),
+        ("6", 2),
+        ("7", 2),
+        ("8", 2),
+        ("9", 1),
+    ];
+    let sorts = [("E", 4), ("F", 8), ("T", 6)];
+    assert_eq!(pushed, counts(&[&shifts[..], &sorts[..]].concat()));
+    let pops = [
+        ("1", 1),
+        ("2", 2),
+        ("5", 4),
+        ("6", 2),
+        ("7", 3),
+        ("8", 5),
+        ("9", 1),
+    ];
+    let goto_pops = [("E", 2), ("F", 4), ("T", 3)];
+    assert_eq!(popped, counts(&[&pops[..], &goto_pops[..]].concat()));
+    assert_eq!(peeked, counts(&[("0", 6), ("5", 6), ("6", 4), ("7", 2)]));
+
+    // the goto nodes per sort peek once per goto of the LR automaton, nothing else is on the stack
+    let goto_peeks = counts(&[("0", 3), ("5", 3), ("6", 2), ("7", 1)]);
+    let reverse_goto = automaton::reverse_goto();
+    let [pushed, popped, peeked] = stack_actions(&reverse_goto);
+    assert_eq!(pushed, counts(&shifts));
+    let pops = [
+        ("1", 1),
+        ("2", 1),
+        ("5", 1),
+        ("6", 2),
+        ("7", 2),
+        ("8", 2),
+        ("9", 1),
+    ];
+    assert_eq!(popped, counts(&pops));
+    assert_eq!(peeked, goto_peeks);
+
+    // the target state is pushed, on the way into the start node too
+    let push_first = automaton::push_first();
+    assert_eq!(push_first.start_actions, [Action::Push("0".to_string())]);
+    let [pushed, popped, peeked] = stack_actions(&push_first);
+    let pushes = [
+        ("0", 1),
+        ("1", 1),
+        ("2", 2),
+        ("3", 3),
+        ("4", 1),
+        ("5", 1),
+        ("6", 2),
+        ("7", 2),
+        ("8", 1),
+        ("9", 1),
+        ("10", 1),
+        ("11", 1),
+    ];
+    assert_eq!(pushed, counts(&pushes));
+    let pops = [
+        ("1", 1),
+        ("2", 2),
+        ("3", 1),
+        ("4", 1),
+        ("5", 1),
+        ("6", 2),
+        ("7", 2),
+        ("8", 2),
+        ("9", 3),
+        ("10", 2),
+        ("11", 1),
+    ];
+    assert_eq!(popped, counts(&pops));
+    assert_eq!(peeked, goto_peeks);
+
+    // only the state numbers that are peeked at are left, and inlining does not change that
+    for automaton in [
+        automaton::minimal_push(),
+        automaton::max_inline_1(),
+        automaton::max_inline_2(),
+    ] {
+        let [pushed, popped, peeked] = stack_actions(&automaton);
+        assert_eq!(pushed, counts(&[("0", 1), ("5", 1), ("6", 2), ("7", 2)]));
+        assert_eq!(popped, counts(&[("5", 1), ("6", 1), ("7", 1)]));
+        assert_eq!(peeked, goto_peeks);
+    }
+}
+
+/// The names of the nodes in each group of inlined nodes
+fn groups(automaton: &Automaton) -> BTreeSet<Vec<&str>> {
+    let nodes = 0..automaton.nodes.len();
+    nodes
+        .clone()
+        .filter(|&n| automaton.root(n) == n)
+        .map(|root| {
+            nodes
+                .clone()
+                .filter(|&n| automaton.root(n) == root)
+                .map(|n| automaton.nodes[n].name.as_str())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn inlining_follows_the_single_incoming_edges() {
+    let inline_1 = automaton::max_inline_1();
+    let inline_2 = automaton::max_inline_2();
+    assert_eq!(
+        groups(&inline_1),
+        BTreeSet::from([
+            vec!["S0/S5/S6/S7", "S4"],
+            vec!["S1", "S8", "S11", "EGoto"],
+            vec!["S2", "S9", "TGoto"],
+            vec!["S3", "S10", "FGoto"],
+            vec!["accept"],
+        ])
+    );
+    assert_eq!(
+        groups(&inline_2),
+        BTreeSet::from([
+            vec!["S0/S5/S6/S7", "S4"],
+            vec!["S1", "S2", "S3", "S8", "S9", "S10", "S11", "EGoto", "TGoto", "FGoto"],
+            vec!["accept"],
+        ])
+    );
+    // inlining does not change the automaton underneath
+    let minimal_push = automaton::minimal_push();
+    assert_eq!(
+        (&inline_2.nodes, &inline_2.edges),
+        (&minimal_push.nodes, &minimal_push.edges)
+    );
+}
+
+/// Checks that the tags in `xml` are balanced and the attribute values are quoted and escaped, and
+///   returns the start and empty-element tags, in order
+fn xml_tags(xml: &str) -> Vec<&str> {
+    let mut open: Vec<&str> = vec![];
+    let mut tags = vec![];
+    let mut rest = xml;
+    while let Some(start) = rest.find('<') {
+        assert!(
+            !rest[..start].contains('>'),
+            "a stray > before {:?}",
+            &rest[start..]
+        );
+        let end = start + rest[start..].find('>').expect("an unclosed tag");
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+        assert!(!tag.contains('<'), "a < in the tag {:?}", tag);
+        assert_eq!(
+            tag.matches('"').count() % 2,
+            0,
+            "an unclosed attribute in {:?}",
+            tag
+        );
+        for entity in tag.split('&').skip(1) {
+            let known = ["amp;", "lt;", "gt;", "quot;"];
+            assert!(
+                known.iter().any(|e| entity.starts_with(e)),
+                "a stray & in {:?}",
+                tag
+            );
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            assert_eq!(open.pop(), Some(name), "a mismatched end tag");
+        } else {
+            tags.push(tag);
+            if !tag.ends_with('/') {
+                open.push(tag.split_whitespace().next().unwrap());
+            }
+        }
+    }
+    assert!(
+        rest.trim().is_empty() && open.is_empty(),
+        "unclosed elements {:?}",
+        open
+    );
+    tags
+}
+
+/// The value of the attribute `name` in a tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {}=\"", name))? + name.len() + 3;
+    Some(&tag[start..start + tag[start..].find('"')?])
+}
+
+#[test]
+fn drawio_is_well_formed() {
+    for (name, automaton) in stages() {
+        let xml = automaton.to_drawio();
+        let tags = xml_tags(&xml);
+        assert!(tags.starts_with(&["mxfile"]), "{}", name);
+        let cells: Vec<&str> = tags
+            .into_iter()
+            .filter(|t| t.starts_with("mxCell "))
+            .collect();
+        let ids: Vec<&str> = cells.iter().map(|c| attribute(c, "id").unwrap()).collect();
+        let unique: BTreeSet<&str> = ids.iter().copied().collect();
+        assert_eq!(unique.len(), ids.len(), "{}: duplicate ids", name);
+        for cell in &cells {
+            for reference in ["parent", "source", "target"] {
+                if let Some(id) = attribute(cell, reference) {
+                    assert!(unique.contains(id), "{}: {} refers to {}", name, cell, id);
+                }
+            }
+        }
+        let vertices = cells.iter().filter(|c| c.contains(" vertex=\"1\"")).count();
+        let edges = cells.iter().filter(|c| c.contains(" edge=\"1\"")).count();
+        let containers = cells.iter().filter(|c| c.contains("container=1;")).count();
+        // the start point and its edge come on top of the nodes and edges
+        let nodes = automaton.nodes.len();
+        assert_eq!(vertices, nodes + 1 + containers, "{}", name);
+        assert_eq!(edges, automaton.edges.len() + 1, "{}", name);
+    }
+}
+
+#[test]
+fn dot_is_well_formed() {
+    for (name, automaton) in stages() {
+        let dot = automaton.to_dot();
+        assert!(
+            dot.starts_with("digraph {\n") && dot.ends_with("\n}"),
+            "{}",
+            name
+        );
+        assert_eq!(
+            dot.matches('{').count(),
+            dot.matches('}').count(),
+            "{}",
+            name
+        );
+        assert_eq!(
+            dot.matches("<table").count(),
+            automaton.nodes.len() - 1,
+            "{}",
+            name
+        );
+        assert_eq!(
+            dot.matches("<table").count(),
+            dot.matches("</table>").count(),
+            "{}",
+            name
+        );
+        assert_eq!(
+            dot.matches("<tr>").count(),
+            dot.matches("</tr>").count(),
+            "{}",
+            name
+        );
+        let mut declared = BTreeSet::from(["start".to_string()]);
+        let mut edges = 0;
+        for line in dot.lines().map(str::trim) {
+            assert_eq!(line.matches('"').count() % 2, 0, "{}: {}", name, line);
+            if let Some((from, rest)) = line.split_once(" -> ") {
+                let to = rest.split(' ').next().unwrap();
+                assert!(
+                    declared.contains(from) && declared.contains(to),
+                    "{}: {}",
+                    name,
+                    line
+                );
+                edges += 1;
+            } else if let Some((node, _)) = line.split_once(" [") {
+                if node.starts_with('n') && node != "node" {
+                    assert!(
+                        declared.insert(node.to_string()),
+                        "{}: {} twice",
+                        name,
+                        node
+                    );
+                }
+            }
+        }
+        assert_eq!(declared.len(), automaton.nodes.len() + 1, "{}", name);
+        assert_eq!(edges, automaton.edges.len() + 1, "{}", name);
+    }
+}
+
+/// Undoes the escaping of the XML attribute and then of the HTML inside it, and returns the lines
+///   of text between the `<div>`s and `<br>`s without the spaces, which the hand-drawn diagrams use
+///   for the layout
+fn lines(value: &str) -> Vec<String> {
+    let html = value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&amp;", "&");
+    let mut text = String::new();
+    let mut rest = html.as_str();
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        if rest[start..].starts_with("<div") || rest[start..].starts_with("<br") {
+            text.push('\n');
+        }
+        rest = &rest[start + rest[start..].find('>').unwrap() + 1..];
+    }
+    text.push_str(rest);
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<String>())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// The items of each node by name, and how often each edge label occurs
+type Labels = (BTreeMap<String, BTreeSet<String>>, BTreeMap<String, usize>);
+
+/// The labels in the style of the diagrams in the blog post, where `S0/S5/S6/S7` is `S0S5S6S7`
+fn labels(automaton: &Automaton) -> Labels {
+    let strip = |s: &str| s.split_whitespace().collect::<String>();
+    let nodes = automaton
+        .nodes
+        .iter()
+        .enumerate()
+        .filter(|&(n, _)| n != automaton.accept)
+        .map(|(_, node)| {
+            let items = node.items.iter().map(|item| strip(item)).collect();
+            (node.name.replace('/', ""), items)
+        })
+        .collect();
+    let mut edges = BTreeMap::new();
+    let options = automaton.edges.iter().map(|e| e.options.clone());
+    for options in options.chain([vec![automaton.start_actions.clone()]]) {
+        let label: String = options
+            .iter()
+            .flatten()
+            .map(|a| strip(&a.to_string()))
+            .collect();
+        if !label.is_empty() {
+            *edges.entry(label).or_insert(0) += 1;
+        }
+    }
+    (nodes, edges)
+}
+
+/// The labels of a hand-drawn diagram, where the edge labels are on the edges or on a label of
+///   their own
+fn drawio_labels(xml: &str) -> Labels {
+    let (mut nodes, mut edges) = (BTreeMap::new(), BTreeMap::new());
+    for cell in xml_tags(xml)
+        .into_iter()
+        .filter(|t| t.starts_with("mxCell "))
+    {
+        let lines = lines(attribute(cell, "value").unwrap_or(""));
+        let style = attribute(cell, "style").unwrap_or("");
+        if lines.is_empty() {
+            continue;
+        } else if cell.contains(" vertex=\"1\"") && !style.starts_with("edgeLabel") {
+            nodes.insert(lines[0].clone(), lines[1..].iter().cloned().collect());
+        } else {
+            *edges.entry(lines.concat()).or_insert(0) += 1;
+        }
+    }
+    (nodes, edges)
+}
+
+#[test]
+fn agrees_with_the_diagrams_in_the_blog_post() {
+    for (name, automaton) in [
+        ("lalr-automaton", automaton::lalr()),
+        ("push-first", automaton::push_first()),
+        ("minimal-push", automaton::minimal_push()),
+    ] {
+        let path = format!(
+            "{}/../../content/optimising-lr-automata/{}.drawio",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        let xml = std::fs::read_to_string(&path).expect("the diagram should be readable");
+        assert_eq!(labels(&automaton), drawio_labels(&xml), "{}", name);
+    }
+}
@@ -0,0 +1,105 @@
+use optimising_recursive_ascent_part_2::sequence::{parse_longest_prefix, Expressions};
+use optimising_recursive_ascent_part_2::StackLabel;
+use recursive_ascent_core::{Error, DEFAULT_MAX_DEPTH};
+
+fn longest_prefix(input: &str) -> Result<(Vec<&'static str>, &str), Error> {
+    let mut reductions = vec![];
+    let rest =
+        parse_longest_prefix::<_, Vec<StackLabel>>(input, &mut reductions, DEFAULT_MAX_DEPTH)?;
+    Ok((reductions, rest))
+}
+
+fn reductions(input: &str) -> Vec<&'static str> {
+    longest_prefix(input).unwrap().0
+}
+
+#[test]
+fn the_whole_input() {
+    assert_eq!(longest_prefix("a+a"), Ok((reductions("a+a"), "")));
+    assert_eq!(
+        reductions("a+a"),
+        [
+            "F = a",
+            "T = F",
+            "E = T",
+            "F = a",
+            "T = F",
+            "E = E + T",
+            "S = E"
+        ]
+    );
+}
+
+#[test]
+fn stops_at_a_token_that_cannot_continue() {
+    assert_eq!(longest_prefix("a+a)a"), Ok((reductions("a+a"), ")a")));
+    assert_eq!(longest_prefix("(a)a"), Ok((reductions("(a)"), "a")));
+}
+
+#[test]
+fn goes_back_over_a_dangling_operator() {
+    assert_eq!(longest_prefix("a+"), Ok((reductions("a"), "+")));
+    assert_eq!(longest_prefix("a*"), Ok((reductions("a"), "*")));
+    assert_eq!(longest_prefix("a+b"), Ok((reductions("a"), "+b")));
+    assert_eq!(longest_prefix("a*a+(a"), Ok((reductions("a*a"), "+(a")));
+    assert_eq!(longest_prefix("(a)*(a+"), Ok((reductions("(a)"), "*(a+")));
+}
+
+#[test]
+fn no_prefix_is_an_expression() {
+    assert_eq!(longest_prefix(""), Err(Error::EOF));
+    assert_eq!(longest_prefix("(a"), Err(Error::EOF));
+    assert_eq!(longest_prefix("+a"), Err(Error::Unexpected('+')));
+    assert_eq!(longest_prefix("(a+)"), Err(Error::Unexpected(')')));
+}
+
+#[test]
+fn too_deep_is_not_backtracked() {
+    let input = format!("a+{}", "(".repeat(DEFAULT_MAX_DEPTH));
+    assert_eq!(
+        longest_prefix(&input),
+        Err(Error::TooDeep {
+            depth: DEFAULT_MAX_DEPTH + 1,
+            offset: input.len() - 1,
+        })
+    );
+}
+
+fn expressions(input: &str) -> Vec<Result<Vec<&'static str>, Error>> {
+    Expressions::<Vec<&'static str>, Vec<StackLabel>>::new(input, DEFAULT_MAX_DEPTH).collect()
+}
+
+#[test]
+fn expressions_are_separated_by_semicolons_and_newlines() {
+    assert_eq!(
+        expressions("a;a+a\n(a)"),
+        [
+            Ok(reductions("a")),
+            Ok(reductions("a+a")),
+            Ok(reductions("(a)"))
+        ]
+    );
+}
+
+#[test]
+fn empty_expressions_are_skipped() {
+    assert_eq!(
+        expressions(";\na;;\n\na*a;"),
+        [Ok(reductions("a")), Ok(reductions("a*a"))]
+    );
+    assert_eq!(expressions(""), []);
+    assert_eq!(expressions(";\n;"), []);
+}
+
+#[test]
+fn an_error_skips_the_rest_of_its_expression() {
+    assert_eq!(
+        expressions("a+;a)a\n(a;a"),
+        [
+            Err(Error::Unexpected(';')),
+            Err(Error::Unexpected(')')),
+            Err(Error::Unexpected(';')),
+            Ok(reductions("a")),
+        ]
+    );
+}
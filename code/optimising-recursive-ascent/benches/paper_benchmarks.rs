@@ -1,5 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion, BenchmarkId};
-use optimizing_directly_executable_lr_parsers::paper;
+use optimising_recursive_ascent::paper;
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
 
 pub fn parse(c: &mut Criterion) {
     let sample_input = "a+a*(a+a)*a";
@@ -7,27 +8,27 @@ pub fn parse(c: &mut Criterion) {
         BenchmarkId::new("parse", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        paper::parse(&mut str.chars().peekable()).expect("sample_input should parse just fine")));
+        paper::parse::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_reverse_goto", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        paper::parse_reverse_goto(&mut str.chars().peekable()).expect("sample_input should parse_reverse_goto just fine")));
+        paper::parse_reverse_goto::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_reverse_goto just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_chain_elim", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        paper::parse_chain_elim(&mut str.chars().peekable()).expect("sample_input should parse_chain_elim just fine")));
+        paper::parse_chain_elim::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_chain_elim just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_minpush", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        paper::parse_minpush(&mut str.chars().peekable()).expect("sample_input should parse_minpush just fine")));
+        paper::parse_minpush::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_minpush just fine")));
     c.bench_with_input(
         BenchmarkId::new("parse_max_inline", sample_input),
         &sample_input,
         |b, &str| b.iter(||
-        paper::parse_max_inline(&mut str.chars().peekable()).expect("sample_input should parse_max_inline just fine")));
+        paper::parse_max_inline::<_, Vec<_>>(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).expect("sample_input should parse_max_inline just fine")));
 }
 
 criterion_group!(benches, parse);
@@ -1,5 +1,7 @@
-use crate::{outprod, Error, Iter, Sort, State};
-use std::hint::unreachable_unchecked;
+use recursive_ascent_core::stack::ParseStack;
+use recursive_ascent_core::{
+    peek, step, top, unreachable_label, Actions, DepthLimit, Error, Iter, Sort, State,
+};
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 enum State_ {
@@ -21,20 +23,26 @@ enum State_ {
     S7Goto(Sort),
 }
 
-pub fn parse(input: &mut Iter) -> Result<(), Error> {
+pub fn parse<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State_::*;
 
-    let mut stack = vec![];
+    let limit = DepthLimit::new(max_depth, 0, input);
+    let mut stack = S::from_slice(&[]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -49,24 +57,24 @@ pub fn parse(input: &mut Iter) -> Result<(), Error> {
             }
             S1 => match input.next() {
                 Some('+') => {
-                    stack.push(1);
+                    limit.push(&mut stack, 1, input)?;
                     label = S6;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => {
-                    outprod("S = E");
+                    actions.outprod("S = E");
                     return Ok(());
                 }
             },
-            S2 => match input.peek() {
+            S2 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
-                    stack.push(2);
+                    limit.push(&mut stack, 2, input)?;
                     label = S7;
                 }
                 _ => {
-                    outprod("E = T");
-                    label = match stack.last().unwrap() {
+                    actions.outprod("E = T");
+                    label = match stack.peek().unwrap() {
                         0 => S0Goto(Sort::E),
                         5 => S5Goto(Sort::E),
                         _ => unreachable!(),
@@ -74,8 +82,8 @@ pub fn parse(input: &mut Iter) -> Result<(), Error> {
                 }
             },
             S3 => {
-                outprod("T = F");
-                label = match stack.last().unwrap() {
+                actions.outprod("T = F");
+                label = match stack.peek().unwrap() {
                     0 => S0Goto(Sort::T),
                     5 => S5Goto(Sort::T),
                     6 => S6Goto(Sort::T),
@@ -83,8 +91,8 @@ pub fn parse(input: &mut Iter) -> Result<(), Error> {
                 }
             }
             S4 => {
-                outprod("F = a");
-                label = match stack.last().unwrap() {
+                actions.outprod("F = a");
+                label = match stack.peek().unwrap() {
                     0 => S0Goto(Sort::F),
                     5 => S5Goto(Sort::F),
                     6 => S6Goto(Sort::F),
@@ -94,11 +102,11 @@ pub fn parse(input: &mut Iter) -> Result<(), Error> {
             }
             S5 => match input.next() {
                 Some('a') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S5; // (self)
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -113,11 +121,11 @@ pub fn parse(input: &mut Iter) -> Result<(), Error> {
             }
             S6 => match input.next() {
                 Some('a') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -132,11 +140,11 @@ pub fn parse(input: &mut Iter) -> Result<(), Error> {
             }
             S7 => match input.next() {
                 Some('a') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -148,27 +156,27 @@ pub fn parse(input: &mut Iter) -> Result<(), Error> {
             }
             S8 => match input.next() {
                 Some('+') => {
-                    stack.push(8);
+                    limit.push(&mut stack, 8, input)?;
                     label = S6;
                 }
                 Some(')') => {
-                    stack.push(8);
+                    limit.push(&mut stack, 8, input)?;
                     label = S11;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S9 => match input.peek() {
+            S9 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
-                    stack.push(9);
+                    limit.push(&mut stack, 9, input)?;
                     label = S7;
                 }
                 _ => {
                     let _ = stack.pop(); // 6
                     let _ = stack.pop(); // 1 or 8
-                    outprod("E = E + T");
-                    label = match stack.last().unwrap() {
+                    actions.outprod("E = E + T");
+                    label = match stack.peek().unwrap() {
                         0 => S0Goto(Sort::E),
                         5 => S5Goto(Sort::E),
                         _ => unreachable!(),
@@ -178,8 +186,8 @@ pub fn parse(input: &mut Iter) -> Result<(), Error> {
             S10 => {
                 let _ = stack.pop(); // 7
                 let _ = stack.pop(); // 2 or 9
-                outprod("T = T * F");
-                label = match stack.last().unwrap() {
+                actions.outprod("T = T * F");
+                label = match stack.peek().unwrap() {
                     0 => S0Goto(Sort::T),
                     5 => S5Goto(Sort::T),
                     6 => S6Goto(Sort::T),
@@ -189,8 +197,8 @@ pub fn parse(input: &mut Iter) -> Result<(), Error> {
             S11 => {
                 let _ = stack.pop(); // 8
                 let _ = stack.pop(); // 5
-                outprod("F = ( E )");
-                label = match stack.last().unwrap() {
+                actions.outprod("F = ( E )");
+                label = match stack.peek().unwrap() {
                     0 => S0Goto(Sort::F),
                     5 => S5Goto(Sort::F),
                     6 => S6Goto(Sort::F),
@@ -204,20 +212,26 @@ pub fn parse(input: &mut Iter) -> Result<(), Error> {
 
 /// Note the Goto is now labeled with the sort, and checks the stack for the state
 ///   (instead of the other way)
-pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_reverse_goto<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
 
-    let mut stack = vec![];
+    let limit = DepthLimit::new(max_depth, 0, input);
+    let mut stack = S::from_slice(&[]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -225,41 +239,41 @@ pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
             },
             S1 => match input.next() {
                 Some('+') => {
-                    stack.push(1);
+                    limit.push(&mut stack, 1, input)?;
                     label = S6;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => {
-                    outprod("S = E");
+                    actions.outprod("S = E");
                     return Ok(());
                 }
             },
-            S2 => match input.peek() {
+            S2 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
-                    stack.push(2);
+                    limit.push(&mut stack, 2, input)?;
                     label = S7;
                 }
                 _ => {
-                    outprod("E = T");
+                    actions.outprod("E = T");
                     label = EGoto
                 }
             },
             S3 => {
-                outprod("T = F");
+                actions.outprod("T = F");
                 label = TGoto
             }
             S4 => {
-                outprod("F = a");
+                actions.outprod("F = a");
                 label = FGoto
             }
             S5 => match input.next() {
                 Some('a') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S5; // (self)
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -267,11 +281,11 @@ pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
             },
             S6 => match input.next() {
                 Some('a') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -279,11 +293,11 @@ pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
             },
             S7 => match input.next() {
                 Some('a') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S4;
                 }
                 Some('(') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -291,50 +305,50 @@ pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
             },
             S8 => match input.next() {
                 Some('+') => {
-                    stack.push(8);
+                    limit.push(&mut stack, 8, input)?;
                     label = S6;
                 }
                 Some(')') => {
-                    stack.push(8);
+                    limit.push(&mut stack, 8, input)?;
                     label = S11;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S9 => match input.peek() {
+            S9 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
-                    stack.push(9);
+                    limit.push(&mut stack, 9, input)?;
                     label = S7;
                 }
                 _ => {
                     let _ = stack.pop(); // 6
                     let _ = stack.pop(); // 1
-                    outprod("E = E + T");
+                    actions.outprod("E = E + T");
                     label = EGoto
                 }
             },
             S10 => {
                 let _ = stack.pop(); // 7
                 let _ = stack.pop(); // 2 or 9
-                outprod("T = T * F");
+                actions.outprod("T = T * F");
                 label = TGoto
             }
             S11 => {
                 let _ = stack.pop(); // 8
                 let _ = stack.pop(); // 5
-                outprod("F = ( E )");
+                actions.outprod("F = ( E )");
                 label = FGoto
             }
-            EGoto => match stack[stack.len() - 1] {
+            EGoto => match top(&label, &stack, &[0, 5]) {
                 5 => label = S8,
                 _ => label = S1,
             },
-            TGoto => match stack[stack.len() - 1] {
+            TGoto => match top(&label, &stack, &[0, 5, 6]) {
                 6 => label = S9,
                 _ => label = S2,
             },
-            FGoto => match stack[stack.len() - 1] {
+            FGoto => match top(&label, &stack, &[0, 5, 6, 7]) {
                 7 => label = S10,
                 _ => label = S3,
             },
@@ -343,22 +357,28 @@ pub fn parse_reverse_goto(input: &mut Iter) -> Result<(), Error> {
 }
 
 /// Note that S3 and S4 (which were just jumps) were inlined, and then any gotos were inlined
-pub fn parse_chain_elim(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_chain_elim<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
 
-    let mut stack = vec![];
+    let limit = DepthLimit::new(max_depth, 0, input);
+    let mut stack = S::from_slice(&[]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    stack.push(0);
-                    outprod("F = a");
-                    outprod("T = F");
+                    limit.push(&mut stack, 0, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     label = S2;
                 }
                 Some('(') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -366,35 +386,35 @@ pub fn parse_chain_elim(input: &mut Iter) -> Result<(), Error> {
             },
             S1 => match input.next() {
                 Some('+') => {
-                    stack.push(1);
+                    limit.push(&mut stack, 1, input)?;
                     label = S6;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => {
-                    outprod("S = E");
+                    actions.outprod("S = E");
                     return Ok(());
                 }
             },
-            S2 => match input.peek() {
+            S2 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
-                    stack.push(2);
+                    limit.push(&mut stack, 2, input)?;
                     label = S7;
                 }
                 _ => {
-                    outprod("E = T");
+                    actions.outprod("E = T");
                     label = EGoto
                 }
             },
             S5 => match input.next() {
                 Some('a') => {
-                    stack.push(5);
-                    outprod("F = a");
-                    outprod("T = F");
+                    limit.push(&mut stack, 5, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     label = S2;
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S5; // (self)
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -402,13 +422,13 @@ pub fn parse_chain_elim(input: &mut Iter) -> Result<(), Error> {
             },
             S6 => match input.next() {
                 Some('a') => {
-                    stack.push(6);
-                    outprod("F = a");
-                    outprod("T = F");
+                    limit.push(&mut stack, 6, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     label = S9;
                 }
                 Some('(') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -416,12 +436,12 @@ pub fn parse_chain_elim(input: &mut Iter) -> Result<(), Error> {
             },
             S7 => match input.next() {
                 Some('a') => {
-                    stack.push(7);
-                    outprod("F = a");
+                    limit.push(&mut stack, 7, input)?;
+                    actions.outprod("F = a");
                     label = S10;
                 }
                 Some('(') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -429,57 +449,57 @@ pub fn parse_chain_elim(input: &mut Iter) -> Result<(), Error> {
             },
             S8 => match input.next() {
                 Some('+') => {
-                    stack.push(8);
+                    limit.push(&mut stack, 8, input)?;
                     label = S6;
                 }
                 Some(')') => {
-                    stack.push(8);
+                    limit.push(&mut stack, 8, input)?;
                     label = S11;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S9 => match input.peek() {
+            S9 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
-                    stack.push(9);
+                    limit.push(&mut stack, 9, input)?;
                     label = S7;
                 }
                 _ => {
                     let _ = stack.pop(); // 6
                     let _ = stack.pop(); // 1
-                    outprod("E = E + T");
+                    actions.outprod("E = E + T");
                     label = EGoto
                 }
             },
             S10 => {
                 let _ = stack.pop(); // 7
                 let _ = stack.pop(); // 2 or 9
-                outprod("T = T * F");
+                actions.outprod("T = T * F");
                 label = TGoto
             }
             S11 => {
                 let _ = stack.pop(); // 8
                 let _ = stack.pop(); // 5
-                outprod("F = ( E )");
+                actions.outprod("F = ( E )");
                 label = FGoto
             }
-            EGoto => match stack[stack.len() - 1] {
+            EGoto => match top(&label, &stack, &[0, 5]) {
                 5 => label = S8,
                 _ => label = S1,
             },
-            TGoto => match stack[stack.len() - 1] {
+            TGoto => match top(&label, &stack, &[0, 5, 6]) {
                 6 => label = S9,
                 _ => label = S2,
             },
-            FGoto => match stack[stack.len() - 1] {
+            FGoto => match top(&label, &stack, &[0, 5, 6, 7]) {
                 7 => label = S10,
                 _ => {
-                    outprod("T = F");
+                    actions.outprod("T = F");
                     label = TGoto
                 }
             },
-            S3 | S4 => unsafe { unreachable_unchecked() },
+            S3 | S4 => unsafe { unreachable_label(&label, &stack) },
         }
     }
 }
@@ -489,22 +509,28 @@ pub fn parse_chain_elim(input: &mut Iter) -> Result<(), Error> {
 ///  (5/6/7). We could eliminate stack number 0 in theory according to the push graph minimal vertex
 ///  cover, but then the stack might be empty in some situation where we want to view the top of the
 ///  stack.
-pub fn parse_minpush(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_minpush<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
 
-    let mut stack = vec![];
+    let limit = DepthLimit::new(max_depth, 0, input);
+    let mut stack = S::from_slice(&[]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    stack.push(0);
-                    outprod("F = a");
-                    outprod("T = F");
+                    limit.push(&mut stack, 0, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     label = S2;
                 }
                 Some('(') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -516,29 +542,29 @@ pub fn parse_minpush(input: &mut Iter) -> Result<(), Error> {
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => {
-                    outprod("S = E");
+                    actions.outprod("S = E");
                     return Ok(());
                 }
             },
-            S2 => match input.peek() {
+            S2 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
                     label = S7;
                 }
                 _ => {
-                    outprod("E = T");
+                    actions.outprod("E = T");
                     label = EGoto
                 }
             },
             S5 => match input.next() {
                 Some('a') => {
-                    stack.push(5);
-                    outprod("F = a");
-                    outprod("T = F");
+                    limit.push(&mut stack, 5, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     label = S2;
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S5; // (self)
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -546,13 +572,13 @@ pub fn parse_minpush(input: &mut Iter) -> Result<(), Error> {
             },
             S6 => match input.next() {
                 Some('a') => {
-                    stack.push(6);
-                    outprod("F = a");
-                    outprod("T = F");
+                    limit.push(&mut stack, 6, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     label = S9;
                 }
                 Some('(') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -560,12 +586,12 @@ pub fn parse_minpush(input: &mut Iter) -> Result<(), Error> {
             },
             S7 => match input.next() {
                 Some('a') => {
-                    stack.push(7);
-                    outprod("F = a");
+                    limit.push(&mut stack, 7, input)?;
+                    actions.outprod("F = a");
                     label = S10;
                 }
                 Some('(') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -581,89 +607,95 @@ pub fn parse_minpush(input: &mut Iter) -> Result<(), Error> {
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S9 => match input.peek() {
+            S9 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
                     label = S7;
                 }
                 _ => {
                     let _ = stack.pop(); // 6
-                    outprod("E = E + T");
+                    actions.outprod("E = E + T");
                     label = EGoto
                 }
             },
             S10 => {
                 let _ = stack.pop(); // 7
-                outprod("T = T * F");
+                actions.outprod("T = T * F");
                 label = TGoto
             }
             S11 => {
                 let _ = stack.pop(); // 5
-                outprod("F = ( E )");
+                actions.outprod("F = ( E )");
                 label = FGoto
             }
-            EGoto => match stack[stack.len() - 1] {
+            EGoto => match top(&label, &stack, &[0, 5]) {
                 5 => label = S8,
                 _ => label = S1,
             },
-            TGoto => match stack[stack.len() - 1] {
+            TGoto => match top(&label, &stack, &[0, 5, 6]) {
                 6 => label = S9,
                 _ => label = S2,
             },
-            FGoto => match stack[stack.len() - 1] {
+            FGoto => match top(&label, &stack, &[0, 5, 6, 7]) {
                 7 => label = S10,
                 _ => {
-                    outprod("T = F");
+                    actions.outprod("T = F");
                     label = TGoto
                 }
             },
-            S3 | S4 => unsafe { unreachable_unchecked() },
+            S3 | S4 => unsafe { unreachable_label(&label, &stack) },
         }
     }
 }
 
 /// Now we can inline all labels used only once. We go from 2 unused labels to 6, with 9 labels left
 ///   in use.
-pub fn parse_max_inline(input: &mut Iter) -> Result<(), Error> {
+pub fn parse_max_inline<A: Actions, S: ParseStack<Value = i32>>(
+    input: &mut Iter,
+    actions: &mut A,
+    max_depth: usize,
+) -> Result<(), Error> {
     use State::*;
 
-    let mut stack = vec![];
+    let limit = DepthLimit::new(max_depth, 0, input);
+    let mut stack = S::from_slice(&[]);
     let mut label = S0;
     loop {
+        step(actions, &label, &stack, input);
         match label {
             S0 => match input.next() {
                 Some('a') => {
-                    stack.push(0);
-                    outprod("F = a");
-                    outprod("T = F");
+                    limit.push(&mut stack, 0, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     label = S2;
                 }
                 Some('(') => {
-                    stack.push(0);
+                    limit.push(&mut stack, 0, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S2 => match input.peek() {
+            S2 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
                     label = S7;
                 }
                 _ => {
-                    outprod("E = T");
+                    actions.outprod("E = T");
                     label = EGoto
                 }
             },
             S5 => match input.next() {
                 Some('a') => {
-                    stack.push(5);
-                    outprod("F = a");
-                    outprod("T = F");
+                    limit.push(&mut stack, 5, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     label = S2;
                 }
                 Some('(') => {
-                    stack.push(5);
+                    limit.push(&mut stack, 5, input)?;
                     label = S5; // (self)
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -671,12 +703,13 @@ pub fn parse_max_inline(input: &mut Iter) -> Result<(), Error> {
             },
             S6 => match input.next() {
                 Some('a') => {
-                    stack.push(6);
-                    outprod("F = a");
+                    limit.push(&mut stack, 6, input)?;
+                    actions.outprod("F = a");
+                    actions.outprod("T = F");
                     label = S9;
                 }
                 Some('(') => {
-                    stack.push(6);
+                    limit.push(&mut stack, 6, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
@@ -684,45 +717,45 @@ pub fn parse_max_inline(input: &mut Iter) -> Result<(), Error> {
             },
             S7 => match input.next() {
                 Some('a') => {
-                    stack.push(7);
-                    outprod("F = a");
+                    limit.push(&mut stack, 7, input)?;
+                    actions.outprod("F = a");
                     label = S10
                 }
                 Some('(') => {
-                    stack.push(7);
+                    limit.push(&mut stack, 7, input)?;
                     label = S5;
                 }
                 Some(c) => return Err(Error::Unexpected(c)),
                 None => return Err(Error::EOF),
             },
-            S9 => match input.peek() {
+            S9 => match peek(input) {
                 Some('*') => {
                     let _ = input.next();
                     label = S7;
                 }
                 _ => {
                     let _ = stack.pop(); // 6
-                    outprod("E = E + T");
+                    actions.outprod("E = E + T");
                     label = EGoto
                 }
             },
             S10 => {
                 let _ = stack.pop(); // 7
-                outprod("T = T * F");
+                actions.outprod("T = T * F");
                 label = TGoto
             }
-            EGoto => match stack[stack.len() - 1] {
+            EGoto => match top(&label, &stack, &[0, 5]) {
                 5 => match input.next() {
                     Some('+') => {
                         label = S6;
                     }
                     Some(')') => {
                         let _ = stack.pop(); // 5
-                        outprod("F = ( E )");
-                        match stack[stack.len() - 1] {
+                        actions.outprod("F = ( E )");
+                        match top(&label, &stack, &[0, 5, 6, 7]) {
                             7 => label = S10,
                             _ => {
-                                outprod("T = F");
+                                actions.outprod("T = F");
                                 label = TGoto
                             }
                         }
@@ -736,16 +769,16 @@ pub fn parse_max_inline(input: &mut Iter) -> Result<(), Error> {
                     }
                     Some(c) => return Err(Error::Unexpected(c)),
                     None => {
-                        outprod("S = E");
+                        actions.outprod("S = E");
                         return Ok(());
                     }
                 },
             },
-            TGoto => match stack[stack.len() - 1] {
+            TGoto => match top(&label, &stack, &[0, 5, 6]) {
                 6 => label = S9,
                 _ => label = S2,
             },
-            S1 | S3 | S4 | S8 | S11 | FGoto => unsafe { unreachable_unchecked() },
+            S1 | S3 | S4 | S8 | S11 | FGoto => unsafe { unreachable_label(&label, &stack) },
         }
     }
 }
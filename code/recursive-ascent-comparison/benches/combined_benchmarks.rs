@@ -0,0 +1,79 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use recursive_ascent_comparison::{built_variants, generated, variants};
+use recursive_ascent_core::eval::Evaluator;
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+
+const SAMPLE_INPUT: &str = "a+a*(a+a)*a";
+
+/// The sample input of the blog posts, with the variants of both posts and the parsers that
+///   `build.rs` generates in one group
+pub fn combined(c: &mut Criterion) {
+    let mut group = c.benchmark_group("combined");
+    for (name, parse) in built_variants::<()>() {
+        group.bench_with_input(
+            BenchmarkId::new(name, SAMPLE_INPUT),
+            &SAMPLE_INPUT,
+            |b, &str| {
+                b.iter(|| {
+                    parse(&mut str.chars().peekable(), &mut ()).unwrap_or_else(|e| {
+                        panic!("sample_input should {} just fine: {:?}", name, e)
+                    })
+                })
+            },
+        );
+    }
+    for (name, parse) in variants::<()>() {
+        group.bench_with_input(
+            BenchmarkId::new(name, SAMPLE_INPUT),
+            &SAMPLE_INPUT,
+            |b, &str| {
+                b.iter(|| {
+                    parse(&mut str.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH).unwrap_or_else(
+                        |e| panic!("sample_input should {} just fine: {:?}", name, e),
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// The same with the value of the sample input computed during the parse, so the semantic actions
+///   do real work. Also the parser that `grammar!` generates for the grammar, which computes the
+///   value itself.
+pub fn evaluate(c: &mut Criterion) {
+    let env = [2, 3, 5, 7];
+    let mut group = c.benchmark_group("evaluate");
+    for (name, parse) in variants::<Evaluator>() {
+        group.bench_with_input(
+            BenchmarkId::new(name, SAMPLE_INPUT),
+            &SAMPLE_INPUT,
+            |b, &str| {
+                b.iter(|| {
+                    let mut evaluator = Evaluator::new(&env);
+                    parse(
+                        &mut str.chars().peekable(),
+                        &mut evaluator,
+                        DEFAULT_MAX_DEPTH,
+                    )
+                    .unwrap_or_else(|e| panic!("sample_input should {} just fine: {:?}", name, e));
+                    evaluator.value()
+                })
+            },
+        );
+    }
+    group.bench_with_input(
+        BenchmarkId::new("grammar!", SAMPLE_INPUT),
+        &SAMPLE_INPUT,
+        |b, &str| {
+            b.iter(|| {
+                generated::parse(str)
+                    .unwrap_or_else(|e| panic!("sample_input should parse just fine: {:?}", e))
+            })
+        },
+    );
+    group.finish();
+}
+
+criterion_group!(benches, combined, evaluate);
+criterion_main!(benches);
@@ -0,0 +1,33 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use recursive_ascent_comparison::variants;
+use recursive_ascent_core::inputs::{generate, Shape};
+
+const SIZES: [usize; 6] = [10, 100, 1_000, 10_000, 100_000, 1_000_000];
+
+/// A group per input shape, with every variant on inputs of 10 up to 1M tokens. The throughput
+///   is in bytes (= tokens) per second, so the sizes can be compared. There is no depth limit,
+///   the parentheses of the largest inputs are much deeper than the default.
+pub fn scaling(c: &mut Criterion) {
+    for shape in Shape::ALL {
+        let mut group = c.benchmark_group(format!("scaling/{}", shape.name()));
+        for size in SIZES {
+            let input = generate(shape, size);
+            group.throughput(Throughput::Bytes(input.len() as u64));
+            if size >= 100_000 {
+                group.sample_size(10);
+            }
+            for (name, parse) in variants::<()>() {
+                group.bench_with_input(BenchmarkId::new(name, size), &input, |b, input| {
+                    b.iter(|| {
+                        parse(&mut input.chars().peekable(), &mut (), usize::MAX)
+                            .unwrap_or_else(|e| panic!("{} should parse the input: {:?}", name, e))
+                    })
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, scaling);
+criterion_main!(benches);
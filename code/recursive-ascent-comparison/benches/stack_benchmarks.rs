@@ -0,0 +1,38 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use recursive_ascent_comparison::{
+    variants_with, Backend, UseArray, UseBits, UseBump, UseCachedTop, UseVec,
+};
+use recursive_ascent_core::inputs::{generate, Shape};
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+
+const SHAPES: [Shape; 3] = [Shape::Balanced, Shape::Parens, Shape::Random];
+const SIZE: usize = 1_000;
+
+fn backend<B: Backend>(c: &mut Criterion, shape: Shape, input: &str) {
+    let mut group = c.benchmark_group(format!("stack/{}/{}", shape.name(), B::NAME));
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    for (name, parse) in variants_with::<(), B>() {
+        group.bench_with_input(BenchmarkId::new(name, SIZE), input, |b, input| {
+            b.iter(|| {
+                parse(&mut input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH)
+                    .unwrap_or_else(|e| panic!("{} should parse the input: {:?}", name, e))
+            })
+        });
+    }
+    group.finish();
+}
+
+/// A group per input shape and stack backend, with every variant on inputs of 1000 tokens
+pub fn stacks(c: &mut Criterion) {
+    for shape in SHAPES {
+        let input = generate(shape, SIZE);
+        backend::<UseVec>(c, shape, &input);
+        backend::<UseCachedTop>(c, shape, &input);
+        backend::<UseArray>(c, shape, &input);
+        backend::<UseBump>(c, shape, &input);
+        backend::<UseBits>(c, shape, &input);
+    }
+}
+
+criterion_group!(benches, stacks);
+criterion_main!(benches);
@@ -0,0 +1,9 @@
+use recursive_ascent_generator::Level;
+
+fn main() {
+    let names = Level::ALL.map(|level| format!("parse_{}", level.name()));
+    let parsers: Vec<_> = names.iter().map(String::as_str).zip(Level::ALL).collect();
+    if let Err(e) = recursive_ascent_generator::build("arithmetic.grammar", &parsers) {
+        panic!("{}", e);
+    }
+}
@@ -0,0 +1,27 @@
+use recursive_ascent_comparison::variant;
+use recursive_ascent_core::bytecode::Compiler;
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+
+/// Prints the stack machine code for the input, optionally with the variant to parse it with, e.g.
+///   `cargo run --bin compile -- "a+a*(a+a)*a" part2::parse_inline2`
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let input = args.next().unwrap_or_else(|| "a+a*(a+a)*a".to_string());
+    let parse = variant(&args.next().unwrap_or_else(|| "part1::parse".to_string()));
+    let mut compiler = Compiler::default();
+    match parse(
+        &mut input.chars().peekable(),
+        &mut compiler,
+        DEFAULT_MAX_DEPTH,
+    ) {
+        Ok(()) => {
+            for op in compiler.code {
+                println!("{}", op);
+            }
+        }
+        Err(e) => {
+            eprintln!("Could not parse {:?}: {:?}", input, e);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use recursive_ascent_comparison::variant;
+use recursive_ascent_core::derivation;
+
+/// Prints the derivation tree of the input given as argument in Graphviz DOT format, optionally
+///   with the variant to parse it with, e.g.
+///   `cargo run --bin derivation_tree -- "a+a*a" part2::parse_single_input_next | dot -Tsvg > tree.svg`
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let input = args.next().unwrap_or_else(|| "a+a*(a+a)*a".to_string());
+    let parse = variant(&args.next().unwrap_or_else(|| "part1::parse".to_string()));
+    match derivation::derive(parse, &input) {
+        Ok(tree) => println!("{}", tree.to_dot()),
+        Err(e) => {
+            eprintln!("Could not parse {:?}: {:?}", input, e);
+            std::process::exit(1);
+        }
+    }
+}
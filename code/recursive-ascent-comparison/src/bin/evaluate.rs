@@ -0,0 +1,51 @@
+use recursive_ascent_comparison::{variant, variants};
+use recursive_ascent_core::bytecode::{Compiler, Vm};
+use recursive_ascent_core::eval::Evaluator;
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+
+/// Evaluates the input with every variant, both during the parse and by running the compiled code,
+///   and exits with an error if any of them disagree. The values bound to the `a`s in turn default
+///   to 2, 3, 5 and 7, e.g.
+///   `cargo run --bin evaluate -- "a+a*(a+a)*a" 1 2 3 4 5`
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(input) = args.next() else {
+        eprintln!("Usage: evaluate <input> [<value of the nth a>...]");
+        std::process::exit(1);
+    };
+    let mut env: Vec<i64> = args
+        .map(|v| {
+            v.parse()
+                .unwrap_or_else(|e| panic!("{:?} is not a value: {}", v, e))
+        })
+        .collect();
+    if env.is_empty() {
+        env = vec![2, 3, 5, 7];
+    }
+    let mut results = vec![];
+    let mut vm = Vm::default();
+    for (name, evaluate) in variants::<Evaluator>() {
+        let mut evaluator = Evaluator::new(&env);
+        let evaluated = evaluate(
+            &mut input.chars().peekable(),
+            &mut evaluator,
+            DEFAULT_MAX_DEPTH,
+        )
+        .map(|()| evaluator.value());
+        let mut compiler = Compiler::default();
+        let compile = variant::<Compiler>(name);
+        let compiled = compile(
+            &mut input.chars().peekable(),
+            &mut compiler,
+            DEFAULT_MAX_DEPTH,
+        )
+        .map(|()| vm.run(&compiler.code, &env));
+        println!("{:34} {:?} {:?}", name, evaluated, compiled);
+        results.push(evaluated);
+        results.push(compiled);
+    }
+    if results.windows(2).any(|pair| pair[0] != pair[1]) {
+        eprintln!("The variants disagree on {:?}", input);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,47 @@
+use recursive_ascent_comparison::variants;
+use recursive_ascent_core::inputs::{generate, Shape};
+use recursive_ascent_core::instrument::{measure, Counts};
+
+const SIZES: [usize; 4] = [10, 100, 1_000, 10_000];
+
+/// Prints the operations per input token of every variant, for each shape of generated input.
+///   `cargo run -p recursive_ascent_comparison --features instrument --bin instrument_report`
+fn main() {
+    for shape in Shape::ALL {
+        let inputs: Vec<String> = SIZES.iter().map(|&n| generate(shape, n)).collect();
+        let tokens: usize = inputs.iter().map(|i| i.len()).sum();
+        println!(
+            "{} ({} inputs, {} tokens)",
+            shape.name(),
+            inputs.len(),
+            tokens
+        );
+        println!(
+            "{:34} {:>8} {:>8} {:>8} {:>8}",
+            "per token", "jumps", "pushes", "pops", "peeks"
+        );
+        for (name, parse) in variants::<()>() {
+            let mut total = Counts::default();
+            for input in &inputs {
+                // no depth limit, the deepest parentheses are deeper than the default
+                let (result, counts) =
+                    measure(|| parse(&mut input.chars().peekable(), &mut (), usize::MAX));
+                result.unwrap_or_else(|e| panic!("{} should parse {}: {:?}", name, input, e));
+                total.jumps += counts.jumps;
+                total.pushes += counts.pushes;
+                total.pops += counts.pops;
+                total.peeks += counts.peeks;
+            }
+            let per_token = |n: u64| n as f64 / tokens as f64;
+            println!(
+                "{:34} {:>8.3} {:>8.3} {:>8.3} {:>8.3}",
+                name,
+                per_token(total.jumps),
+                per_token(total.pushes),
+                per_token(total.pops),
+                per_token(total.peeks)
+            );
+        }
+        println!();
+    }
+}
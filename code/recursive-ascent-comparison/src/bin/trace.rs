@@ -0,0 +1,24 @@
+use recursive_ascent_comparison::variant;
+use recursive_ascent_core::trace::{diff, trace, Tracer};
+
+/// Prints the trace of a parser variant on the input, or the aligned traces of two variants, e.g.
+///   `cargo run --bin trace -- "a+a*a" part1::parse_minpush part2::parse_inline1`
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match &args[..] {
+        [input, name] => print!("{}", trace(variant::<Tracer>(name), input)),
+        [input, left, right] => print!(
+            "{}",
+            diff(
+                left,
+                &trace(variant(left), input),
+                right,
+                &trace(variant(right), input)
+            )
+        ),
+        _ => {
+            eprintln!("Usage: trace <input> <variant> [<variant to compare with>]");
+            std::process::exit(1);
+        }
+    }
+}
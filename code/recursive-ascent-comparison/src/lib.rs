@@ -0,0 +1,182 @@
+//! All the parser variants of both blog posts side by side, for the benchmarks and the tools that
+//!   compare them.
+
+use optimising_recursive_ascent as part1;
+use optimising_recursive_ascent_part_2 as part2;
+use part2::StackLabel;
+use recursive_ascent_core::stack::{
+    ArrayStack, BitStack, BumpStack, CachedTop, ParseStack, StackValue,
+};
+use recursive_ascent_core::{Actions, Error, Iter};
+
+recursive_ascent_macro::grammar! {
+    /// The grammar of the blog posts generated by `grammar!`, with the value of an expression
+    ///   computed like [`recursive_ascent_core::eval::Evaluator`] does with every `a` = 2
+    pub mod generated: i64;
+    S = E => |e| e;
+    E = E "+" T => |e, _, t: i64| e.wrapping_add(t);
+    E = T => |t| t;
+    T = T "*" F => |t, _, f: i64| t.wrapping_mul(f);
+    T = F => |f| f;
+    F = "a" => |_| 2;
+    F = "(" E ")" => |_, e, _| e;
+}
+
+/// The parsers that `build.rs` generates from `arithmetic.grammar`, one function per optimisation
+///   level, named after the level
+pub mod built {
+    include!(concat!(env!("OUT_DIR"), "/arithmetic.rs"));
+}
+
+pub type BuiltVariant<A> = (
+    &'static str,
+    fn(&mut built::Iter, &mut A) -> Result<(), built::Error>,
+);
+
+/// The parsers of [`built`] with the semantic actions `A`, named like the variants
+pub fn built_variants<A: built::Actions>() -> [BuiltVariant<A>; 4] {
+    [
+        ("built::parse_lr", built::parse_lr),
+        ("built::parse_chain_elim", built::parse_chain_elim),
+        ("built::parse_minpush", built::parse_minpush),
+        (
+            "built::parse_single_input_next",
+            built::parse_single_input_next,
+        ),
+    ]
+}
+
+pub type Variant<A> = (&'static str, fn(&mut Iter, &mut A, usize) -> Result<(), Error>);
+
+/// A stack representation to run the variants with
+pub trait Backend {
+    const NAME: &'static str;
+
+    type Stack<T: StackValue>: ParseStack<Value = T>;
+}
+
+pub struct UseVec;
+pub struct UseCachedTop;
+pub struct UseArray;
+pub struct UseBump;
+pub struct UseBits;
+
+impl Backend for UseVec {
+    const NAME: &'static str = "vec";
+    type Stack<T: StackValue> = Vec<T>;
+}
+
+impl Backend for UseCachedTop {
+    const NAME: &'static str = "cached_top";
+    type Stack<T: StackValue> = CachedTop<T>;
+}
+
+/// Deep enough for the benchmarks and the fuzzer, but not for the deepest scaling inputs
+impl Backend for UseArray {
+    const NAME: &'static str = "array";
+    type Stack<T: StackValue> = ArrayStack<T, 16384>;
+}
+
+impl Backend for UseBump {
+    const NAME: &'static str = "bump";
+    type Stack<T: StackValue> = BumpStack<T>;
+}
+
+impl Backend for UseBits {
+    const NAME: &'static str = "bits";
+    type Stack<T: StackValue> = BitStack<T>;
+}
+
+/// The 15 variants, with `$states` for the stacks of state numbers, `$labels` for the stack of
+///   labels and `$parser` for the stack in the `Parser` struct
+macro_rules! variants {
+    ($states:ty, $labels:ty, $parser:ty) => {
+        [
+            ("part1::parse", part1::paper::parse::<A, $states>),
+            (
+                "part1::parse_reverse_goto",
+                part1::paper::parse_reverse_goto::<A, $states>,
+            ),
+            (
+                "part1::parse_chain_elim",
+                part1::paper::parse_chain_elim::<A, $states>,
+            ),
+            (
+                "part1::parse_minpush",
+                part1::paper::parse_minpush::<A, $states>,
+            ),
+            (
+                "part1::parse_max_inline",
+                part1::paper::parse_max_inline::<A, $states>,
+            ),
+            (
+                "part2::parse_reverse_goto",
+                part2::parser::parse_reverse_goto::<A, $states>,
+            ),
+            (
+                "part2::parse_asc_desc",
+                part2::parser::parse_asc_desc::<A, $states>,
+            ),
+            (
+                "part2::parse_push_first",
+                part2::parser::parse_push_first::<A, $states>,
+            ),
+            (
+                "part2::parse_minpush",
+                part2::parser::parse_minpush::<A, $states>,
+            ),
+            (
+                "part2::parse_inline1",
+                part2::parser::parse_inline1::<A, $states>,
+            ),
+            (
+                "part2::parse_inline2",
+                part2::parser::parse_inline2::<A, $states>,
+            ),
+            (
+                "part2::parse_single_input_next1",
+                part2::parser::parse_single_input_next1::<A, $labels>,
+            ),
+            (
+                "part2::parse_single_input_next",
+                part2::parser::parse_single_input_next::<A, $parser>,
+            ),
+            (
+                "part2::paper::parse_parser_struct",
+                part2::paper::parse_parser_struct::<A, $parser>,
+            ),
+            (
+                "part2::paper::parse_single_match",
+                part2::paper::parse_single_match::<A, $parser>,
+            ),
+        ]
+    };
+}
+
+/// Every variant with the semantic actions `A`, named after the crate (blog post) and function,
+///   with the stacks of the blog posts: a `Vec`, with the top kept apart in the `Parser` struct
+pub fn variants<A: Actions>() -> [Variant<A>; 15] {
+    variants!(Vec<i32>, Vec<StackLabel>, CachedTop<StackLabel>)
+}
+
+/// Every variant with the semantic actions `A` and all stacks of backend `B`
+pub fn variants_with<A: Actions, B: Backend>() -> [Variant<A>; 15] {
+    variants!(B::Stack<i32>, B::Stack<StackLabel>, B::Stack<StackLabel>)
+}
+
+/// Looks up a variant by name, or exits with the list of names
+pub fn variant<A: Actions>(name: &str) -> fn(&mut Iter, &mut A, usize) -> Result<(), Error> {
+    let variants = variants();
+    match variants.iter().find(|(n, _)| *n == name) {
+        Some(&(_, parse)) => parse,
+        None => {
+            let names: Vec<&str> = variants.iter().map(|(n, _)| *n).collect();
+            eprintln!(
+                "Unknown variant {}, choose from: {}",
+                name,
+                names.join(", ")
+            );
+            std::process::exit(1);
+        }
+    }
+}
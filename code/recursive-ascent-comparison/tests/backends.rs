@@ -0,0 +1,74 @@
+use recursive_ascent_comparison::{
+    variants, variants_with, Backend, UseArray, UseBits, UseBump, UseCachedTop, UseVec,
+};
+use recursive_ascent_core::inputs::{generate, Shape};
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+
+/// Every variant on backend `B` gives the same result and reductions as on the stacks of the blog
+///   posts, including at the depth limit
+fn agrees_with_vec<B: Backend>() {
+    let mut inputs: Vec<String> = Shape::ALL
+        .iter()
+        .flat_map(|&shape| [1, 9, 200].map(|tokens| generate(shape, tokens)))
+        .collect();
+    inputs.extend(["", "a+", "(a))", "a*b"].map(String::from));
+    inputs.push("(".repeat(DEFAULT_MAX_DEPTH + 1));
+    inputs.push(generate(Shape::Parens, 2 * DEFAULT_MAX_DEPTH + 1));
+    let expected_variants = variants::<Vec<&'static str>>();
+    for input in &inputs {
+        for ((name, parse), (_, expected_parse)) in
+            variants_with::<_, B>().into_iter().zip(expected_variants)
+        {
+            let (mut reductions, mut expected) = (vec![], vec![]);
+            assert_eq!(
+                parse(
+                    &mut input.chars().peekable(),
+                    &mut reductions,
+                    DEFAULT_MAX_DEPTH
+                ),
+                expected_parse(
+                    &mut input.chars().peekable(),
+                    &mut expected,
+                    DEFAULT_MAX_DEPTH
+                ),
+                "{} on {} with {:?}",
+                name,
+                B::NAME,
+                input
+            );
+            assert_eq!(
+                reductions,
+                expected,
+                "{} on {} with {:?}",
+                name,
+                B::NAME,
+                input
+            );
+        }
+    }
+}
+
+#[test]
+fn vec() {
+    agrees_with_vec::<UseVec>();
+}
+
+#[test]
+fn cached_top() {
+    agrees_with_vec::<UseCachedTop>();
+}
+
+#[test]
+fn array() {
+    agrees_with_vec::<UseArray>();
+}
+
+#[test]
+fn bump() {
+    agrees_with_vec::<UseBump>();
+}
+
+#[test]
+fn bits() {
+    agrees_with_vec::<UseBits>();
+}
@@ -0,0 +1,47 @@
+use recursive_ascent_comparison::{built, built_variants, variants};
+use recursive_ascent_core::inputs::{generate, Shape};
+use recursive_ascent_core::{Error, DEFAULT_MAX_DEPTH};
+
+/// The errors of the generated parsers, as the errors of the hand-written ones
+fn error(error: built::Error) -> Error {
+    match error {
+        built::Error::EOF => Error::EOF,
+        built::Error::Unexpected(c) => Error::Unexpected(c),
+    }
+}
+
+/// The parsers generated from `arithmetic.grammar` make the same reductions as `paper::parse` of
+///   the first blog post, and fail with the same errors
+fn agrees_with_paper_parse(input: &str) {
+    let (_, reference) = variants::<Vec<&'static str>>()[0];
+    let mut expected = vec![];
+    let expected_result = reference(
+        &mut input.chars().peekable(),
+        &mut expected,
+        DEFAULT_MAX_DEPTH,
+    );
+    for (name, parse) in built_variants::<Vec<&'static str>>() {
+        let mut reductions = vec![];
+        let result = parse(&mut input.chars().peekable(), &mut reductions).map_err(error);
+        assert_eq!(result, expected_result, "{} on {:?}", name, input);
+        if result.is_ok() {
+            assert_eq!(reductions, expected, "{} on {:?}", name, input);
+        }
+    }
+}
+
+#[test]
+fn same_reductions() {
+    for shape in Shape::ALL {
+        for tokens in [1, 4, 25, 300] {
+            agrees_with_paper_parse(&generate(shape, tokens));
+        }
+    }
+}
+
+#[test]
+fn same_errors() {
+    for input in ["", "a+", "(a", "b", "a)", "a+*a", "(a))", "aa", "()"] {
+        agrees_with_paper_parse(input);
+    }
+}
@@ -0,0 +1,86 @@
+use recursive_ascent_comparison::variants;
+use recursive_ascent_core::bytecode::{Compiler, Op, Vm};
+use recursive_ascent_core::eval::Evaluator;
+use recursive_ascent_core::inputs::{generate, Shape};
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+
+#[test]
+fn postfix_code() {
+    use Op::*;
+    for (name, parse) in variants::<Compiler>() {
+        let mut compiler = Compiler::default();
+        parse(
+            &mut "a+a*(a+a)".chars().peekable(),
+            &mut compiler,
+            DEFAULT_MAX_DEPTH,
+        )
+        .unwrap();
+        assert_eq!(
+            compiler.code,
+            [
+                PushVar(0),
+                PushVar(1),
+                PushVar(2),
+                PushVar(3),
+                Add,
+                Mul,
+                Add
+            ],
+            "{}",
+            name
+        );
+    }
+}
+
+/// The value of `input` that [`Evaluator`] computes with `paper::parse` of the first blog post
+fn evaluate(input: &str, env: &[i64]) -> Option<i64> {
+    let (_, parse) = variants::<Evaluator>()[0];
+    let mut evaluator = Evaluator::new(env);
+    parse(
+        &mut input.chars().peekable(),
+        &mut evaluator,
+        DEFAULT_MAX_DEPTH,
+    )
+    .ok()?;
+    evaluator.value()
+}
+
+#[test]
+fn the_vm_agrees_with_the_evaluator() {
+    let envs: [&[i64]; 3] = [&[3], &[1, -2, 5], &[i64::MAX, 7]];
+    let mut vm = Vm::default();
+    for shape in Shape::ALL {
+        for tokens in [1, 5, 50, 500] {
+            let input = generate(shape, tokens);
+            for (name, parse) in variants::<Compiler>() {
+                let mut compiler = Compiler::default();
+                parse(
+                    &mut input.chars().peekable(),
+                    &mut compiler,
+                    DEFAULT_MAX_DEPTH,
+                )
+                .unwrap();
+                for env in envs {
+                    assert_eq!(
+                        vm.run(&compiler.code, env),
+                        evaluate(&input, env),
+                        "{} on {:?} with {:?}",
+                        name,
+                        input,
+                        env
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn code_without_exactly_one_value() {
+    let mut vm = Vm::default();
+    assert_eq!(vm.run(&[], &[1]), None);
+    assert_eq!(vm.run(&[Op::PushVar(0), Op::Add], &[1]), None);
+    assert_eq!(vm.run(&[Op::PushVar(0), Op::PushVar(1)], &[1]), None);
+    // the stack of the failed run is not left over for the next one
+    assert_eq!(vm.run(&[Op::PushVar(0)], &[1]), Some(1));
+}
@@ -0,0 +1,33 @@
+use recursive_ascent_comparison::variants;
+use recursive_ascent_core::{Error, DEFAULT_MAX_DEPTH};
+
+/// Every `(` is shifted onto the stack, so one more than the limit fails on the last one in every
+///   variant, whatever the stack starts with
+#[test]
+fn all_variants_agree_on_too_deep() {
+    let input = "(".repeat(DEFAULT_MAX_DEPTH + 1);
+    for (name, parse) in variants::<()>() {
+        assert_eq!(
+            parse(&mut input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH),
+            Err(Error::TooDeep {
+                depth: DEFAULT_MAX_DEPTH + 1,
+                offset: DEFAULT_MAX_DEPTH,
+            }),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn the_limit_itself_is_not_too_deep() {
+    let input = "(".repeat(DEFAULT_MAX_DEPTH);
+    for (name, parse) in variants::<()>() {
+        assert_eq!(
+            parse(&mut input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH),
+            Err(Error::EOF),
+            "{}",
+            name
+        );
+    }
+}
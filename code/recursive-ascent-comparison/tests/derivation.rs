@@ -0,0 +1,123 @@
+use recursive_ascent_comparison::variants;
+use recursive_ascent_core::derivation::{derive, Tree};
+use recursive_ascent_core::inputs::{generate, Shape};
+use recursive_ascent_core::{Error, DEFAULT_MAX_DEPTH};
+
+fn tree(symbol: &'static str, children: Vec<Tree>) -> Tree {
+    Tree { symbol, children }
+}
+
+fn leaf(symbol: &'static str) -> Tree {
+    tree(symbol, vec![])
+}
+
+/// The subtree of an `a`: `F = a` with chain rules to `sorts` on top, innermost first
+fn a(sorts: &[&'static str]) -> Tree {
+    sorts
+        .iter()
+        .fold(tree("F", vec![leaf("a")]), |child, &sort| {
+            tree(sort, vec![child])
+        })
+}
+
+#[test]
+fn every_variant_derives_the_same_tree() {
+    let expected = tree(
+        "S",
+        vec![tree(
+            "E",
+            vec![
+                a(&["T", "E"]),
+                leaf("+"),
+                tree("T", vec![a(&["T"]), leaf("*"), a(&[])]),
+            ],
+        )],
+    );
+    for (name, parse) in variants() {
+        assert_eq!(derive(parse, "a+a*a"), Ok(expected.clone()), "{}", name);
+    }
+}
+
+#[test]
+fn rightmost_derivation() {
+    let (_, parse) = variants()[0];
+    let tree = derive(parse, "a+a*a").unwrap();
+    assert_eq!(
+        tree.rightmost_derivation(),
+        [
+            "S",
+            "E",
+            "E + T",
+            "E + T * F",
+            "E + T * a",
+            "E + F * a",
+            "E + a * a",
+            "T + a * a",
+            "F + a * a",
+            "a + a * a",
+        ]
+    );
+}
+
+#[test]
+fn parentheses_are_leaves_of_their_own() {
+    let (_, parse) = variants()[0];
+    let tree = derive(parse, "(a)").unwrap();
+    assert_eq!(
+        tree.rightmost_derivation().last().map(String::as_str),
+        Some("( a )")
+    );
+}
+
+#[test]
+fn every_variant_reduces_like_paper_parse() {
+    let variants = variants::<Vec<&'static str>>();
+    let (_, reference) = variants[0];
+    for shape in Shape::ALL {
+        for tokens in [1, 2, 7, 30, 101] {
+            let input = generate(shape, tokens);
+            let mut expected = vec![];
+            reference(
+                &mut input.chars().peekable(),
+                &mut expected,
+                DEFAULT_MAX_DEPTH,
+            )
+            .unwrap();
+            for (name, parse) in variants {
+                let mut reductions = vec![];
+                let result = parse(
+                    &mut input.chars().peekable(),
+                    &mut reductions,
+                    DEFAULT_MAX_DEPTH,
+                );
+                assert_eq!(result, Ok(()), "{} on {:?}", name, input);
+                assert_eq!(reductions, expected, "{} on {:?}", name, input);
+            }
+        }
+    }
+}
+
+#[test]
+fn every_variant_rejects_the_same_inputs() {
+    let cases = [
+        ("", Error::EOF),
+        ("a+", Error::EOF),
+        ("(a", Error::EOF),
+        ("b", Error::Unexpected('b')),
+        ("a)", Error::Unexpected(')')),
+        ("a+*a", Error::Unexpected('*')),
+        ("(a))", Error::Unexpected(')')),
+        ("aa", Error::Unexpected('a')),
+    ];
+    for (input, error) in cases {
+        for (name, parse) in variants::<()>() {
+            assert_eq!(
+                parse(&mut input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH),
+                Err(error),
+                "{} on {:?}",
+                name,
+                input
+            );
+        }
+    }
+}
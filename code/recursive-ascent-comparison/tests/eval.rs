@@ -0,0 +1,54 @@
+use recursive_ascent_comparison::variants;
+use recursive_ascent_core::eval::Evaluator;
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+
+fn evaluate(input: &str, env: &[i64]) -> Vec<(&'static str, Option<i64>)> {
+    variants::<Evaluator>()
+        .into_iter()
+        .map(|(name, parse)| {
+            let mut evaluator = Evaluator::new(env);
+            let value = parse(
+                &mut input.chars().peekable(),
+                &mut evaluator,
+                DEFAULT_MAX_DEPTH,
+            )
+            .ok()
+            .and_then(|()| evaluator.value());
+            (name, value)
+        })
+        .collect()
+}
+
+fn assert_value(input: &str, env: &[i64], expected: i64) {
+    for (name, value) in evaluate(input, env) {
+        assert_eq!(value, Some(expected), "{} on {:?}", name, input);
+    }
+}
+
+#[test]
+fn precedence() {
+    assert_value("a+a*a", &[1, 2, 3], 7);
+    assert_value("a*a+a", &[1, 2, 3], 5);
+    assert_value("(a+a)*a", &[1, 2, 3], 9);
+    assert_value("a*(a+a)", &[1, 2, 3], 5);
+}
+
+#[test]
+fn the_env_repeats() {
+    assert_value("a", &[5], 5);
+    assert_value("a+a+a+a", &[1, 10], 22);
+    assert_value("((a))*a*a", &[2], 8);
+}
+
+#[test]
+fn sums_and_products_wrap_around() {
+    assert_value("a+a", &[i64::MAX], -2);
+    assert_value("a*a", &[i64::MIN, -1], i64::MIN);
+}
+
+#[test]
+fn no_value_without_a_parse() {
+    for (name, value) in evaluate("a+", &[1]) {
+        assert_eq!(value, None, "{}", name);
+    }
+}
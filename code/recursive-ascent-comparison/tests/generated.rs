@@ -0,0 +1,42 @@
+use recursive_ascent_comparison::{generated, variants};
+use recursive_ascent_core::eval::Evaluator;
+use recursive_ascent_core::inputs::{generate, Shape};
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+
+/// The parser that `grammar!` generates accepts what `paper::parse` of the first blog post
+///   accepts, with the value that the [`Evaluator`] computes with every `a` = 2
+fn agrees_with_the_evaluator(input: &str) {
+    let (_, parse) = variants::<Evaluator>()[0];
+    let mut evaluator = Evaluator::new(&[2]);
+    let expected = parse(
+        &mut input.chars().peekable(),
+        &mut evaluator,
+        DEFAULT_MAX_DEPTH,
+    )
+    .ok()
+    .and_then(|()| evaluator.value());
+    assert_eq!(generated::parse(input).ok(), expected, "{:?}", input);
+}
+
+#[test]
+fn values() {
+    assert_eq!(generated::parse("a+a*(a+a)"), Ok(10));
+    assert_eq!(generated::parse("(a*a*a)"), Ok(8));
+    for shape in Shape::ALL {
+        for tokens in [1, 4, 25, 300] {
+            agrees_with_the_evaluator(&generate(shape, tokens));
+        }
+    }
+}
+
+#[test]
+fn errors() {
+    assert_eq!(generated::parse("a+"), Err(generated::Error::EOF));
+    assert_eq!(
+        generated::parse("a)"),
+        Err(generated::Error::Unexpected(')'))
+    );
+    for input in ["", "(a", "b", "a+*a", "(a))", "aa", "()"] {
+        agrees_with_the_evaluator(input);
+    }
+}
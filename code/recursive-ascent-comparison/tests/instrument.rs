@@ -0,0 +1,56 @@
+//! Needs the `instrument` feature:
+//!   `cargo test -p recursive_ascent_comparison --features instrument --test instrument`
+
+use recursive_ascent_comparison::variant;
+use recursive_ascent_core::instrument::{measure, Counts};
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+
+fn counts(name: &str, input: &str) -> Counts {
+    let parse = variant::<()>(name);
+    let (result, counts) =
+        measure(|| parse(&mut input.chars().peekable(), &mut (), DEFAULT_MAX_DEPTH));
+    assert_eq!(result, Ok(()), "{} {:?}", name, input);
+    counts
+}
+
+#[test]
+fn counts_of_the_first_and_the_minimal_push_parser() {
+    // a label per state and per goto, and a state number pushed for every shift
+    assert_eq!(
+        counts("part1::parse", "a+a*a"),
+        Counts {
+            jumps: 22,
+            pushes: 5,
+            pops: 4,
+            peeks: 3
+        }
+    );
+    assert_eq!(
+        counts("part1::parse", "(a)"),
+        Counts {
+            jumps: 16,
+            pushes: 3,
+            pops: 2,
+            peeks: 2
+        }
+    );
+    // as many labels, but only the states 6 and 7 of `+` and `*` are pushed, or 5 of `(`
+    assert_eq!(
+        counts("part2::parse_minpush", "a+a*a"),
+        Counts {
+            jumps: 22,
+            pushes: 2,
+            pops: 2,
+            peeks: 3
+        }
+    );
+    assert_eq!(
+        counts("part2::parse_minpush", "(a)"),
+        Counts {
+            jumps: 15,
+            pushes: 1,
+            pops: 1,
+            peeks: 2
+        }
+    );
+}
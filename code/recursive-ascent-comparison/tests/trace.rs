@@ -0,0 +1,107 @@
+use recursive_ascent_comparison::variants;
+use recursive_ascent_core::inputs::{generate, Shape};
+use recursive_ascent_core::trace::{trace, Action, Trace, Tracer};
+use recursive_ascent_core::DEFAULT_MAX_DEPTH;
+
+/// Inputs that every variant accepts, and some that every variant rejects
+fn inputs() -> Vec<String> {
+    let mut inputs: Vec<String> = ["a", "a+a*a", "((a))", "(a+a)*(a)", "a*(a+a)+a"]
+        .into_iter()
+        .chain(["", "a+", "a)", "(a", "aa", "a+*a", "()", "(a))", "b"])
+        .map(String::from)
+        .collect();
+    for shape in Shape::ALL {
+        for tokens in [7, 40] {
+            inputs.push(generate(shape, tokens));
+        }
+    }
+    inputs
+}
+
+fn actions(trace: &Trace) -> Vec<Action> {
+    trace.steps.iter().flat_map(|s| s.actions.clone()).collect()
+}
+
+#[test]
+fn every_variant_shifts_and_reduces_in_the_same_order() {
+    let variants = variants::<Tracer>();
+    let (_, reference) = variants[0];
+    for input in inputs() {
+        // `paper::parse` of the first post goes through a label per state of the LR automaton, so
+        //   a step shifts or reduces at most once and the order does not depend on the inference
+        let expected = trace(reference, &input);
+        for step in &expected.steps {
+            let shifts_and_reductions = step
+                .actions
+                .iter()
+                .filter(|a| matches!(a, Action::Shift(_) | Action::Reduce(_)))
+                .count();
+            assert!(shifts_and_reductions <= 1, "{:?}\n{}", input, expected);
+        }
+        // on an error some variants stop before the reductions that others still do, so only the
+        //   shifts have to agree
+        let compared = |trace: &Trace| -> Vec<Action> {
+            actions(trace)
+                .into_iter()
+                .filter(|a| expected.result.is_ok() || !matches!(a, Action::Reduce(_)))
+                .collect()
+        };
+        for (name, parse) in variants {
+            let trace = trace(parse, &input);
+            assert_eq!(
+                compared(&trace),
+                compared(&expected),
+                "{} {:?}\n{}",
+                name,
+                input,
+                trace
+            );
+        }
+    }
+}
+
+#[test]
+fn traces_agree_with_a_run_without_tracing() {
+    for input in inputs() {
+        for (name, parse) in variants::<Tracer>() {
+            let trace = trace(parse, &input);
+            let mut reductions = vec![];
+            let (_, run) = variants::<Vec<&'static str>>()
+                .into_iter()
+                .find(|(n, _)| *n == name)
+                .unwrap();
+            let result = run(
+                &mut input.chars().peekable(),
+                &mut reductions,
+                DEFAULT_MAX_DEPTH,
+            );
+            assert_eq!(trace.result, result, "{} {:?}", name, input);
+            let actions = actions(&trace);
+            let traced: Vec<&str> = actions
+                .iter()
+                .filter_map(|a| match a {
+                    Action::Reduce(rule) => Some(*rule),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(traced, reductions, "{} {:?}", name, input);
+            let shifted: String = actions
+                .iter()
+                .filter_map(|a| match a {
+                    Action::Shift(c) => Some(*c),
+                    _ => None,
+                })
+                .collect();
+            if result.is_ok() {
+                assert_eq!(shifted, input, "{}", name);
+            } else {
+                assert!(input.starts_with(&shifted), "{} {:?}", name, input);
+            }
+            let end = match result {
+                Ok(()) => Action::Accept,
+                Err(e) => Action::Reject(e),
+            };
+            assert_eq!(actions.last(), Some(&end), "{} {:?}", name, input);
+        }
+    }
+}
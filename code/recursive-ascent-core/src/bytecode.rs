@@ -0,0 +1,80 @@
+//! Compiling the expression to the code of a stack machine while it is parsed. The reductions of
+//!   an LR parser come in postfix order, so every reduction can simply append its instruction.
+
+use crate::Actions;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Op {
+    /// Pushes variable `n`, the value of the `n`th `a` of the input
+    PushVar(usize),
+    /// Pops two values and pushes their sum
+    Add,
+    /// Pops two values and pushes their product
+    Mul,
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Op::PushVar(n) => write!(f, "push_var {}", n),
+            Op::Add => write!(f, "add"),
+            Op::Mul => write!(f, "mul"),
+        }
+    }
+}
+
+/// Emits an instruction for every reduction that computes something
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Compiler {
+    pub code: Vec<Op>,
+    vars: usize,
+}
+
+impl Actions for Compiler {
+    #[inline]
+    fn outprod(&mut self, rule: &'static str) {
+        match rule {
+            "F = a" => {
+                self.code.push(Op::PushVar(self.vars));
+                self.vars += 1;
+            }
+            "E = E + T" => self.code.push(Op::Add),
+            "T = T * F" => self.code.push(Op::Mul),
+            _ => {}
+        }
+    }
+}
+
+/// Runs compiled code, with the stack kept between runs
+#[derive(Debug, Default)]
+pub struct Vm {
+    stack: Vec<i64>,
+}
+
+impl Vm {
+    /// The value of the code with variable `n` bound to `env[n % env.len()]`, like
+    ///   [`crate::eval::Evaluator`] does, or `None` if the code does not leave exactly one value
+    pub fn run(&mut self, code: &[Op], env: &[i64]) -> Option<i64> {
+        self.stack.clear();
+        for &op in code {
+            let value = match op {
+                Op::PushVar(n) => *env.get(n % env.len().max(1))?,
+                Op::Add | Op::Mul => {
+                    let right = self.stack.pop()?;
+                    let left = self.stack.pop()?;
+                    if op == Op::Add {
+                        left.wrapping_add(right)
+                    } else {
+                        left.wrapping_mul(right)
+                    }
+                }
+            };
+            self.stack.push(value);
+        }
+        match self.stack[..] {
+            [value] => Some(value),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{is_sort, Error, Iter, DEFAULT_MAX_DEPTH};
+
+/// A derivation tree. Sorts have the subtrees of their rule as children, terminals are leaves.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Tree {
+    pub symbol: &'static str,
+    pub children: Vec<Tree>,
+}
+
+/// Splits a rule like `"E = E + T"` into `"E"` and `["E", "+", "T"]`
+fn split_rule(
+    rule: &'static str,
+) -> Option<(&'static str, impl DoubleEndedIterator<Item = &'static str>)> {
+    let (lhs, rhs) = rule.split_once(" = ")?;
+    Some((lhs, rhs.split(' ')))
+}
+
+/// Runs a parser variant with the reductions recorded, and turns those into a tree.
+///   For example `derive(optimising_recursive_ascent::paper::parse_minpush::<_, Vec<_>>, "a+a*a")`.
+pub fn derive(
+    parse: fn(&mut Iter, &mut Vec<&'static str>, usize) -> Result<(), Error>,
+    input: &str,
+) -> Result<Tree, Error> {
+    let mut reductions = vec![];
+    parse(&mut input.chars().peekable(), &mut reductions, DEFAULT_MAX_DEPTH)?;
+    Ok(Tree::from_reductions(&reductions)
+        .expect("a successful parse should give a full derivation"))
+}
+
+impl Tree {
+    fn leaf(symbol: &'static str) -> Tree {
+        Tree {
+            symbol,
+            children: vec![],
+        }
+    }
+
+    /// An LR parser reduces in the reverse order of a rightmost derivation. So we can replay the
+    ///   reductions bottom-up with a stack of subtrees, where every sort on the right-hand side of
+    ///   a rule pops a subtree (right to left). Terminals are taken from the rule itself.
+    /// Returns `None` if the reductions do not form exactly one tree.
+    pub fn from_reductions(reductions: &[&'static str]) -> Option<Tree> {
+        let mut stack: Vec<Tree> = vec![];
+        for &rule in reductions {
+            let (lhs, rhs) = split_rule(rule)?;
+            let mut children = vec![];
+            for symbol in rhs.rev() {
+                if is_sort(symbol) {
+                    let child = stack.pop()?;
+                    if child.symbol != symbol {
+                        return None;
+                    }
+                    children.push(child);
+                } else {
+                    children.push(Tree::leaf(symbol));
+                }
+            }
+            children.reverse();
+            stack.push(Tree {
+                symbol: lhs,
+                children,
+            });
+        }
+        match (stack.pop(), stack.is_empty()) {
+            (Some(tree), true) => Some(tree),
+            _ => None,
+        }
+    }
+
+    /// The sentential forms of the rightmost derivation, starting at the root sort and ending in
+    ///   the input (with spaces between the symbols).
+    pub fn rightmost_derivation(&self) -> Vec<String> {
+        let mut form = vec![self];
+        let mut forms = vec![];
+        loop {
+            forms.push(form.iter().map(|t| t.symbol).collect::<Vec<_>>().join(" "));
+            match form.iter().rposition(|t| !t.children.is_empty()) {
+                Some(i) => {
+                    let sort = form[i];
+                    form.splice(i..=i, sort.children.iter());
+                }
+                None => return forms,
+            }
+        }
+    }
+
+    /// Graphviz DOT in the style of the hand-drawn trees in the blog: terminals on one rank in
+    ///   input order, with the edge to a terminal weighted so it goes straight down.
+    pub fn to_dot(&self) -> String {
+        let mut nodes = String::new();
+        let mut edges = String::new();
+        let mut leaves = vec![];
+        self.dot_node(&mut 0, &mut nodes, &mut edges, &mut leaves);
+
+        let mut dot = String::new();
+        dot.push_str("graph {\n");
+        dot.push_str("    bgcolor=\"transparent\";\n");
+        dot.push_str("    ranksep=0.2;\n");
+        dot.push_str("    nodesep=0.01;\n");
+        dot.push_str(
+            "    node [shape=none, height=0.3, color=\"#dddddd\", fontcolor=\"#dddddd\"];\n",
+        );
+        dot.push_str("    edge [color=\"#dddddd\", fontcolor=\"#dddddd\"];\n");
+        dot.push_str(&nodes);
+        dot.push_str("    {\n      rank=\"same\";\n");
+        for (id, symbol) in &leaves {
+            dot.push_str(&format!("      {} [label={}];\n", id, quote(symbol)));
+        }
+        dot.push_str("    }\n");
+        dot.push_str(&edges);
+        if leaves.len() > 1 {
+            dot.push_str("    edge [style=invis, len=0.02];\n");
+            let ids: Vec<&str> = leaves.iter().map(|(id, _)| id.as_str()).collect();
+            dot.push_str(&format!("    {};\n", ids.join(" -- ")));
+        }
+        dot.push('}');
+        dot
+    }
+
+    fn dot_node(
+        &self,
+        next_id: &mut usize,
+        nodes: &mut String,
+        edges: &mut String,
+        leaves: &mut Vec<(String, &'static str)>,
+    ) {
+        let id = format!("n{}", next_id);
+        *next_id += 1;
+        if self.children.is_empty() {
+            leaves.push((id, self.symbol));
+            return;
+        }
+        nodes.push_str(&format!("    {} [label={}];\n", id, quote(self.symbol)));
+        for child in &self.children {
+            if child.children.is_empty() {
+                edges.push_str(&format!("    {} -- n{} [weight=10];\n", id, next_id));
+            } else {
+                edges.push_str(&format!("    {} -- n{};\n", id, next_id));
+            }
+            child.dot_node(next_id, nodes, edges, leaves);
+        }
+    }
+}
+
+fn quote(symbol: &str) -> String {
+    format!("\"{}\"", symbol.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,63 @@
+//! Evaluating the expression while it is parsed, as a use of the semantic actions that depends on
+//!   their order: every reduction works on the values of the symbols that were reduced before it.
+
+use crate::Actions;
+
+/// Computes the value of the expression with a stack of values, the `n`th `a` of the input is
+///   `env[n % env.len()]`. Sums and products wrap around on overflow.
+pub struct Evaluator<'e> {
+    env: &'e [i64],
+    next: usize,
+    values: Vec<i64>,
+}
+
+impl<'e> Evaluator<'e> {
+    pub fn new(env: &'e [i64]) -> Self {
+        assert!(
+            !env.is_empty(),
+            "the environment should have a value for `a`"
+        );
+        Evaluator {
+            env,
+            next: 0,
+            values: vec![],
+        }
+    }
+
+    /// The value of the expression, if the reductions made exactly one
+    pub fn value(&self) -> Option<i64> {
+        match self.values[..] {
+            [value] => Some(value),
+            _ => None,
+        }
+    }
+
+    fn operands(&mut self) -> (i64, i64) {
+        let right = self.values.pop();
+        let left = self.values.pop();
+        left.zip(right)
+            .expect("a binary rule should be reduced after both of its operands")
+    }
+}
+
+impl Actions for Evaluator<'_> {
+    #[inline]
+    fn outprod(&mut self, rule: &'static str) {
+        match rule {
+            "F = a" => {
+                self.values.push(self.env[self.next % self.env.len()]);
+                self.next += 1;
+            }
+            "E = E + T" => {
+                let (left, right) = self.operands();
+                self.values.push(left.wrapping_add(right));
+            }
+            "T = T * F" => {
+                let (left, right) = self.operands();
+                self.values.push(left.wrapping_mul(right));
+            }
+            // the value of the only symbol on the right-hand side
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::is_sort;
+use std::fmt;
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Symbol {
+    Sort(String),
+    /// The input is read a character at a time, so every terminal is one character
+    Terminal(char),
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Symbol::Sort(sort) => write!(f, "{}", sort),
+            Symbol::Terminal(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Rule {
+    pub lhs: String,
+    pub rhs: Vec<Symbol>,
+}
+
+/// Written like the rules in the comment block of the grammar in `lib.rs`, `E = E + T`. This
+///   is also what the generated parsers pass to `outprod`.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} =", self.lhs)?;
+        for symbol in &self.rhs {
+            write!(f, " {}", symbol)?;
+        }
+        Ok(())
+    }
+}
+
+/// A context-free grammar where the first rule is the start rule. Its sort has no other rules and
+///   is not used on the right-hand side of any rule, like `S = E` in the grammar of the blog posts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grammar {
+    rules: Vec<Rule>,
+}
+
+impl Grammar {
+    pub fn new(rules: Vec<Rule>) -> Result<Grammar, String> {
+        let start = match rules.first() {
+            Some(rule) => &rule.lhs,
+            None => return Err("the grammar has no rules".to_string()),
+        };
+        if rules[1..].iter().any(|rule| &rule.lhs == start) {
+            return Err(format!(
+                "the start sort {} should only have the first rule",
+                start
+            ));
+        }
+        for rule in &rules {
+            for symbol in &rule.rhs {
+                match symbol {
+                    Symbol::Sort(sort) if sort == start => {
+                        return Err(format!(
+                            "the start sort {} should not be used in the rule {}",
+                            start, rule
+                        ))
+                    }
+                    Symbol::Sort(sort) if !rules.iter().any(|r| &r.lhs == sort) => {
+                        return Err(format!(
+                            "the sort {} in the rule {} has no rules",
+                            sort, rule
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(Grammar { rules })
+    }
+
+    /// Reads a grammar written like the comment block of the grammar in `lib.rs`, a rule per
+    ///   line with the symbols separated by spaces: `E = E + T`. Words that start with an uppercase
+    ///   letter are sorts, any other single character is a terminal. Empty lines and lines that
+    ///   start with `//` are skipped.
+    pub fn parse(text: &str) -> Result<Grammar, String> {
+        let mut rules = vec![];
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {}", number + 1, message);
+            let mut words = line.split_whitespace();
+            let lhs = match (words.next(), words.next()) {
+                (Some(lhs), Some("=")) if is_sort(lhs) => lhs.to_string(),
+                _ => return Err(error(format!("expected a sort and `=` in {:?}", line))),
+            };
+            let rhs = words
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match (chars.next(), chars.next()) {
+                        _ if is_sort(word) => Ok(Symbol::Sort(word.to_string())),
+                        (Some(c), None) => Ok(Symbol::Terminal(c)),
+                        _ => Err(error(format!(
+                            "{:?} is neither a sort nor a terminal of one character",
+                            word
+                        ))),
+                    }
+                })
+                .collect::<Result<_, _>>()?;
+            rules.push(Rule { lhs, rhs });
+        }
+        Grammar::new(rules)
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// The sorts other than the start sort, in the order of their first rule
+    pub fn sorts(&self) -> Vec<&str> {
+        let mut sorts = vec![];
+        for rule in &self.rules[1..] {
+            if !sorts.contains(&rule.lhs.as_str()) {
+                sorts.push(rule.lhs.as_str());
+            }
+        }
+        sorts
+    }
+}
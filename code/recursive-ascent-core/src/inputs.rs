@@ -0,0 +1,139 @@
+//! Generated inputs for the grammar, in shapes that exercise different parts of the parsers.
+//!   Every token is a single character, so the length of an input is its number of tokens.
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Shape {
+    /// `a+a+...+a`
+    Sums,
+    /// `a*a*...*a`
+    Products,
+    /// `((...(a)...))`
+    Parens,
+    /// A balanced tree of sums and products, like `(a*a+a*a)*(a*a+a*a)+...`
+    Balanced,
+    /// A pseudo-random mix of all the constructs, the same for every call
+    Random,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 5] = [
+        Shape::Sums,
+        Shape::Products,
+        Shape::Parens,
+        Shape::Balanced,
+        Shape::Random,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Shape::Sums => "sums",
+            Shape::Products => "products",
+            Shape::Parens => "parens",
+            Shape::Balanced => "balanced",
+            Shape::Random => "random",
+        }
+    }
+}
+
+/// A sentence of the grammar with the given shape and at most `tokens` tokens (but at least one)
+pub fn generate(shape: Shape, tokens: usize) -> String {
+    let tokens = tokens.max(1);
+    let mut out = String::with_capacity(tokens);
+    match shape {
+        Shape::Sums => separated(&mut out, '+', tokens.div_ceil(2)),
+        Shape::Products => separated(&mut out, '*', tokens.div_ceil(2)),
+        Shape::Parens => {
+            let depth = (tokens - 1) / 2;
+            out.extend(std::iter::repeat_n('(', depth));
+            out.push('a');
+            out.extend(std::iter::repeat_n(')', depth));
+        }
+        Shape::Balanced => balanced(&mut out, tokens, Level::Sum),
+        Shape::Random => random(&mut out, tokens),
+    }
+    out
+}
+
+fn separated(out: &mut String, separator: char, operands: usize) {
+    out.push('a');
+    for _ in 1..operands {
+        out.push(separator);
+        out.push('a');
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Level {
+    Sum,
+    Product,
+    Parens,
+}
+
+/// Splits the budget in two at every level, so the nesting is logarithmic in the length
+fn balanced(out: &mut String, budget: usize, level: Level) {
+    match level {
+        Level::Sum | Level::Product if budget >= 3 => {
+            let (operator, operands) = match level {
+                Level::Sum => ('+', Level::Product),
+                _ => ('*', Level::Parens),
+            };
+            let left = (budget - 1) / 2;
+            balanced(out, left, operands);
+            out.push(operator);
+            balanced(out, budget - 1 - left, operands);
+        }
+        Level::Parens if budget >= 5 => {
+            out.push('(');
+            balanced(out, budget - 2, Level::Sum);
+            out.push(')');
+        }
+        _ => out.push('a'),
+    }
+}
+
+/// A fixed linear congruential generator, so the random inputs are the same on every machine
+struct Lcg(u64);
+
+impl Lcg {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % n
+    }
+}
+
+fn random(out: &mut String, tokens: usize) {
+    let mut rng = Lcg(tokens as u64);
+    let mut open = 0;
+    loop {
+        // an operand, opening parentheses only while there is room to close them again
+        while out.len() + open + 3 <= tokens && rng.below(4) == 0 {
+            out.push('(');
+            open += 1;
+        }
+        out.push('a');
+        // closing parentheses or an operator, as long as there is room for another operand
+        loop {
+            if out.len() + open + 2 > tokens {
+                out.extend(std::iter::repeat_n(')', open));
+                return;
+            }
+            match rng.below(5) {
+                0 if open > 0 => {
+                    out.push(')');
+                    open -= 1;
+                }
+                0..=2 => {
+                    out.push('+');
+                    break;
+                }
+                _ => {
+                    out.push('*');
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Counts of the operations the blog posts use to argue about the optimisations: label jumps,
+//!   pushes and pops on the stack, and peeks at the input. The counting only happens with the
+//!   `instrument` feature, without it the counters compile to nothing.
+
+#[cfg(feature = "instrument")]
+use std::cell::Cell;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Counts {
+    /// Iterations of the loop of a parser, each of which dispatches on the label
+    pub jumps: u64,
+    pub pushes: u64,
+    pub pops: u64,
+    /// Input peeks, reading the input with `next` is not counted
+    pub peeks: u64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum Event {
+    Jump,
+    Push,
+    Pop,
+    Peek,
+}
+
+#[cfg(feature = "instrument")]
+thread_local! {
+    static COUNTS: Cell<Counts> = Cell::new(Counts::default());
+}
+
+#[cfg(feature = "instrument")]
+#[inline(always)]
+pub(crate) fn count(event: Event) {
+    COUNTS.with(|counts| {
+        let mut c = counts.get();
+        match event {
+            Event::Jump => c.jumps += 1,
+            Event::Push => c.pushes += 1,
+            Event::Pop => c.pops += 1,
+            Event::Peek => c.peeks += 1,
+        }
+        counts.set(c);
+    })
+}
+
+#[cfg(not(feature = "instrument"))]
+#[inline(always)]
+pub(crate) fn count(_event: Event) {}
+
+/// Runs `f` and returns its result with the operations counted on this thread while it ran,
+///   e.g. `measure(|| parse_minpush::<_, Vec<_>>(&mut "a+a".chars().peekable(), &mut (), 100))`.
+#[cfg(feature = "instrument")]
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Counts) {
+    let before = COUNTS.with(|counts| counts.replace(Counts::default()));
+    let result = f();
+    let counts = COUNTS.with(|counts| counts.replace(before));
+    (result, counts)
+}
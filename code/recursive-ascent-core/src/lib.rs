@@ -0,0 +1,271 @@
+//! The grammar, the semantic actions and the instrumentation shared by the recursive ascent
+//!   parsers of both blog posts.
+
+pub mod bytecode;
+pub mod derivation;
+pub mod eval;
+pub mod grammar;
+pub mod inputs;
+pub mod instrument;
+pub mod lr0;
+pub mod stack;
+pub mod trace;
+
+use instrument::{count, Event};
+use stack::ParseStack;
+use std::fmt::Debug;
+use std::hint::unreachable_unchecked;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/*
+S = E
+E = E + T
+E = T
+T = T * F
+T = F
+F = a
+F = ( E )
+ */
+
+/// Sorts are words that start with an uppercase letter, like the `E`, `T` and `F` above. Any other
+///   symbol in a rule is a terminal.
+pub fn is_sort(symbol: &str) -> bool {
+    symbol.starts_with(|c: char| c.is_ascii_uppercase())
+        && symbol
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+pub type Iter<'a> = Peekable<Chars<'a>>;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Sort {
+    // S,
+    E,
+    T,
+    F,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum State {
+    S0,
+    S1,
+    S2,
+    S3,
+    S4,
+    S5,
+    S6,
+    S7,
+    S8,
+    S9,
+    S10,
+    S11,
+    EGoto,
+    TGoto,
+    FGoto,
+}
+
+/// The semantic actions of a parser run. `outprod` is called once per reduction with the rule
+///   that was reduced, so the sequence of calls is a rightmost derivation in reverse.
+pub trait Actions {
+    fn outprod(&mut self, rule: &'static str);
+
+    /// Whether the parser should call `step`. The snapshot of the stack is only made when this
+    ///   is true, so the parsers stay as fast as before when not tracing.
+    const TRACE: bool = false;
+
+    /// Called at the start of every loop iteration with the label, the stack from bottom to top,
+    ///   the lookahead and the number of bytes left in the input.
+    fn step(
+        &mut self,
+        _label: String,
+        _stack: Vec<i32>,
+        _lookahead: Option<char>,
+        _remaining: usize,
+    ) {
+    }
+}
+
+/// No semantic actions, for the benchmarks. `outprod` is still a real call, so the benchmarks pay
+///   for a semantic action at every reduction like the numbers in the blog posts do.
+impl Actions for () {
+    #[inline(never)]
+    fn outprod(&mut self, _rule: &'static str) {}
+}
+
+/// Records the reductions, see [`derivation::Tree::from_reductions`]
+impl Actions for Vec<&'static str> {
+    fn outprod(&mut self, rule: &'static str) {
+        self.push(rule)
+    }
+}
+
+/// The stack as a list of state numbers, for tracing
+pub trait Snapshot {
+    fn snapshot(&self) -> Vec<i32>;
+}
+
+impl<S: ParseStack> Snapshot for S {
+    fn snapshot(&self) -> Vec<i32> {
+        self.to_vec().into_iter().map(Into::into).collect()
+    }
+}
+
+/// The start of an iteration of the loop of a parser, the jump to the next label
+#[inline(always)]
+pub fn step<A: Actions>(
+    actions: &mut A,
+    label: &impl Debug,
+    stack: &impl Snapshot,
+    input: &mut Iter,
+) {
+    count(Event::Jump);
+    if A::TRACE {
+        let lookahead = input.peek().copied();
+        let remaining = bytes_left(input);
+        actions.step(
+            format!("{:?}", label),
+            stack.snapshot(),
+            lookahead,
+            remaining,
+        );
+    }
+}
+
+/// The number of bytes left in `input`. Unlike the number of characters left this takes constant
+///   time, so a trace stays linear in the length of the input: the upper bound of the size hint of
+///   `Chars` is the number of bytes it has left, and a peeked character counts as one on top.
+fn bytes_left(input: &mut Iter) -> usize {
+    match input.peek().copied() {
+        Some(c) => {
+            let (_, upper) = input.size_hint();
+            c.len_utf8() + upper.expect("`Chars` should have an upper bound") - 1
+        }
+        None => 0,
+    }
+}
+
+/// `input.peek()`, counted when instrumented
+#[inline(always)]
+pub fn peek<'i>(input: &'i mut Iter) -> Option<&'i char> {
+    count(Event::Peek);
+    input.peek()
+}
+
+/// The top of the stack where a variant relies on it being one of `expected`, e.g. a goto with a
+///   catch-all arm. This is only checked with the `checked` feature.
+#[inline(always)]
+pub fn top<S: ParseStack>(label: &impl Debug, stack: &S, expected: &[S::Value]) -> S::Value {
+    let top = stack.peek();
+    if cfg!(feature = "checked") && !top.is_some_and(|top| expected.contains(&top)) {
+        let expected: Vec<i32> = expected.iter().map(|&t| t.into()).collect();
+        panic!(
+            "{:?} expected one of {:?} on top of the stack, but the stack is {:?}",
+            label,
+            expected,
+            stack.snapshot()
+        );
+    }
+    top.expect("the stack should not be empty")
+}
+
+/// Marks a label that a variant never jumps to. With the `checked` feature reaching it panics with
+///   the label and the stack.
+///
+/// # Safety
+/// Without the `checked` feature reaching it is undefined behaviour, so the variant must really
+///   never jump to the label.
+#[inline(always)]
+pub unsafe fn unreachable_label(label: &impl Debug, stack: &impl Snapshot) -> ! {
+    if cfg!(feature = "checked") {
+        panic!(
+            "reached {:?}, which should be unreachable, with the stack {:?}",
+            label,
+            stack.snapshot()
+        );
+    }
+    unsafe { unreachable_unchecked() }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    EOF,
+    Unexpected(char),
+    /// A push would have put `depth` entries on the stack, more than the limit. `offset` is the
+    ///   offset in the input of the character that the push was for: the one that was shifted, or
+    ///   the lookahead of a goto. The entries that the stack starts with do not count.
+    TooDeep {
+        depth: usize,
+        offset: usize,
+    },
+}
+
+/// A limit on the depth of the stack that is high enough for any input a person would write, but
+///   keeps untrusted input like a million `(` from growing the stack without bound
+pub const DEFAULT_MAX_DEPTH: usize = 10_000;
+
+/// The depth limit of a parser run, with the start of the input to tell the offset of an error
+pub struct DepthLimit<'a> {
+    max_depth: usize,
+    /// The depth of the stack at the start, like the `SL0` at the bottom in part 2
+    bottom: usize,
+    start: Iter<'a>,
+}
+
+impl<'a> DepthLimit<'a> {
+    pub fn new(max_depth: usize, bottom: usize, input: &Iter<'a>) -> Self {
+        DepthLimit {
+            max_depth,
+            bottom,
+            start: input.clone(),
+        }
+    }
+
+    /// `stack.push(value)` for a shift, after the shifted character was read from `input`, or
+    ///   [`Error::TooDeep`] if that would push more entries than the limit
+    #[inline(always)]
+    pub fn push<S: ParseStack>(
+        &self,
+        stack: &mut S,
+        value: S::Value,
+        input: &Iter,
+    ) -> Result<(), Error> {
+        self.push_at(stack, value, || self.read(input) - 1)
+    }
+
+    /// `stack.push(value)` for a goto, before its lookahead is read from `input`, or
+    ///   [`Error::TooDeep`] if that would push more entries than the limit
+    #[inline(always)]
+    pub fn push_goto<S: ParseStack>(
+        &self,
+        stack: &mut S,
+        value: S::Value,
+        input: &Iter,
+    ) -> Result<(), Error> {
+        self.push_at(stack, value, || self.read(input))
+    }
+
+    /// The number of characters read from `input`
+    fn read(&self, input: &Iter) -> usize {
+        self.start.clone().count() - input.clone().count()
+    }
+
+    #[inline(always)]
+    fn push_at<S: ParseStack>(
+        &self,
+        stack: &mut S,
+        value: S::Value,
+        offset: impl FnOnce() -> usize,
+    ) -> Result<(), Error> {
+        let depth = stack.depth() - self.bottom;
+        if depth >= self.max_depth {
+            return Err(Error::TooDeep {
+                depth: depth + 1,
+                offset: offset(),
+            });
+        }
+        stack.push(value);
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use crate::grammar::{Grammar, Rule, Symbol};
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Item {
+    pub rule: usize,
+    pub dot: usize,
+}
+
+/// The LR(0) item sets and transitions of a grammar. The states are numbered breadth-first, with
+///   the outgoing transitions of a state in the order their symbols show up in its items. That
+///   gives the same numbering as the state labels of the blog posts.
+pub struct Lr0<'g> {
+    pub grammar: &'g Grammar,
+    pub states: Vec<Vec<Item>>,
+    /// In the order they were found, so also by source state
+    transitions: Vec<(usize, &'g Symbol, usize)>,
+    targets: HashMap<(usize, &'g Symbol), usize>,
+    /// The sources of the transitions into every state
+    incoming: Vec<Vec<usize>>,
+}
+
+impl<'g> Lr0<'g> {
+    pub fn new(grammar: &'g Grammar) -> Lr0<'g> {
+        let mut lr0 = Lr0 {
+            grammar,
+            states: vec![],
+            transitions: vec![],
+            targets: HashMap::new(),
+            incoming: vec![],
+        };
+        lr0.states.push(lr0.closure(vec![Item { rule: 0, dot: 0 }]));
+        let mut todo = 0;
+        while todo < lr0.states.len() {
+            let mut symbols = vec![];
+            for &item in &lr0.states[todo] {
+                if let Some(symbol) = lr0.next_symbol(item) {
+                    if !symbols.contains(&symbol) {
+                        symbols.push(symbol);
+                    }
+                }
+            }
+            for symbol in symbols {
+                let kernel = lr0.states[todo]
+                    .iter()
+                    .filter(|&&item| lr0.next_symbol(item) == Some(symbol))
+                    .map(|&Item { rule, dot }| Item { rule, dot: dot + 1 })
+                    .collect();
+                let state = lr0.closure(kernel);
+                let same =
+                    |s: &Vec<Item>| s.len() == state.len() && s.iter().all(|i| state.contains(i));
+                let target = match lr0.states.iter().position(same) {
+                    Some(target) => target,
+                    None => {
+                        lr0.states.push(state);
+                        lr0.states.len() - 1
+                    }
+                };
+                lr0.transitions.push((todo, symbol, target));
+                lr0.targets.insert((todo, symbol), target);
+            }
+            todo += 1;
+        }
+        lr0.incoming = vec![vec![]; lr0.states.len()];
+        for &(from, _, to) in &lr0.transitions {
+            lr0.incoming[to].push(from);
+        }
+        lr0
+    }
+
+    pub fn rule(&self, item: Item) -> &'g Rule {
+        &self.grammar.rules()[item.rule]
+    }
+
+    pub fn next_symbol(&self, item: Item) -> Option<&'g Symbol> {
+        self.rule(item).rhs.get(item.dot)
+    }
+
+    fn closure(&self, mut items: Vec<Item>) -> Vec<Item> {
+        let mut i = 0;
+        while i < items.len() {
+            if let Some(Symbol::Sort(sort)) = self.next_symbol(items[i]) {
+                for (rule, r) in self.grammar.rules().iter().enumerate() {
+                    let item = Item { rule, dot: 0 };
+                    if &r.lhs == sort && !items.contains(&item) {
+                        items.push(item);
+                    }
+                }
+            }
+            i += 1;
+        }
+        items
+    }
+
+    /// The transitions `(from, symbol, to)`, by source state
+    pub fn transitions(&self) -> &[(usize, &'g Symbol, usize)] {
+        &self.transitions
+    }
+
+    pub fn goto(&self, state: usize, symbol: &'g Symbol) -> Option<usize> {
+        self.targets.get(&(state, symbol)).copied()
+    }
+
+    /// All state sequences `q0 .. qn` of `n + 1` states that end in `state`. Every transition
+    ///   into a state reads the same symbol, so these are the paths that read the last `n`
+    ///   symbols of the items of `state`.
+    pub fn paths(&self, state: usize, n: usize) -> Vec<Vec<usize>> {
+        let mut paths = vec![vec![state]];
+        for _ in 0..n {
+            paths = paths
+                .iter()
+                .flat_map(|path| {
+                    self.incoming[path[0]].iter().map(move |&from| {
+                        let mut longer = vec![from];
+                        longer.extend(path);
+                        longer
+                    })
+                })
+                .collect();
+        }
+        paths
+    }
+
+    /// Whether rules start in the state, so a goto can come back to it
+    pub fn predicts(&self, state: usize) -> bool {
+        self.states[state]
+            .iter()
+            .any(|item| item.dot == 0 && item.rule != 0)
+    }
+
+    /// The rule of a state that does nothing but reduce it, like `F = a •`
+    pub fn only_reduction(&self, state: usize) -> Option<usize> {
+        match self.states[state][..] {
+            [item] if item.rule != 0 && self.next_symbol(item).is_none() => Some(item.rule),
+            _ => None,
+        }
+    }
+
+    /// The (non-start) rules that are reduced in a state, and whether the start rule is
+    pub fn reductions(&self, state: usize) -> (Vec<usize>, bool) {
+        let mut rules = vec![];
+        let mut accept = false;
+        for &item in &self.states[state] {
+            if self.next_symbol(item).is_none() {
+                if item.rule == 0 {
+                    accept = true;
+                } else {
+                    rules.push(item.rule);
+                }
+            }
+        }
+        (rules, accept)
+    }
+}
@@ -0,0 +1,395 @@
+//! The stack of the parsers behind a trait, so the same variant can run with different stack
+//!   representations. That separates the cost of the representation from the cost of the control
+//!   flow that the optimisations change.
+
+use crate::instrument::{count, Event};
+use bumpalo::Bump;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+/// What the parsers keep on the stack: state numbers or labels. Every value has a code of `BITS`
+///   bits for the packed [`BitStack`].
+pub trait StackValue: Copy + Debug + PartialEq + Into<i32> {
+    /// A power of two, so the codes never straddle two words
+    const BITS: u32;
+
+    fn to_bits(self) -> u64;
+
+    fn from_bits(bits: u64) -> Self;
+}
+
+/// State numbers, of which the grammar has 12
+impl StackValue for i32 {
+    const BITS: u32 = 4;
+
+    #[inline(always)]
+    fn to_bits(self) -> u64 {
+        debug_assert!(
+            (0..16).contains(&self),
+            "state {} does not fit in 4 bits",
+            self
+        );
+        self as u64
+    }
+
+    #[inline(always)]
+    fn from_bits(bits: u64) -> Self {
+        bits as i32
+    }
+}
+
+pub trait ParseStack {
+    type Value: StackValue;
+
+    /// A stack with the values, from bottom to top
+    fn from_slice(values: &[Self::Value]) -> Self;
+
+    fn push(&mut self, value: Self::Value);
+
+    fn pop(&mut self) -> Option<Self::Value>;
+
+    fn peek(&self) -> Option<Self::Value>;
+
+    fn depth(&self) -> usize;
+
+    /// Drops the values above the first `depth` without counting them as pops, but keeps any
+    ///   allocation for the next parse
+    fn truncate(&mut self, depth: usize);
+
+    /// The values from bottom to top
+    fn to_vec(&self) -> Vec<Self::Value>;
+}
+
+impl<T: StackValue> ParseStack for Vec<T> {
+    type Value = T;
+
+    fn from_slice(values: &[T]) -> Self {
+        values.to_vec()
+    }
+
+    #[inline(always)]
+    fn push(&mut self, value: T) {
+        count(Event::Push);
+        Vec::push(self, value)
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<T> {
+        count(Event::Pop);
+        Vec::pop(self)
+    }
+
+    #[inline(always)]
+    fn peek(&self) -> Option<T> {
+        self.last().copied()
+    }
+
+    #[inline(always)]
+    fn depth(&self) -> usize {
+        self.len()
+    }
+
+    fn truncate(&mut self, depth: usize) {
+        Vec::truncate(self, depth)
+    }
+
+    fn to_vec(&self) -> Vec<T> {
+        self.clone()
+    }
+}
+
+/// A `Vec` with the top of the stack kept apart, like the `Parser` struct of the second blog post
+///   did. Peeking at the top does not touch the `Vec`.
+pub struct CachedTop<T> {
+    below: Vec<T>,
+    top: Option<T>,
+}
+
+impl<T: StackValue> ParseStack for CachedTop<T> {
+    type Value = T;
+
+    fn from_slice(values: &[T]) -> Self {
+        match values.split_last() {
+            Some((&top, below)) => CachedTop {
+                below: below.to_vec(),
+                top: Some(top),
+            },
+            None => CachedTop {
+                below: vec![],
+                top: None,
+            },
+        }
+    }
+
+    #[inline(always)]
+    fn push(&mut self, value: T) {
+        count(Event::Push);
+        if let Some(top) = self.top.replace(value) {
+            self.below.push(top);
+        }
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<T> {
+        count(Event::Pop);
+        std::mem::replace(&mut self.top, self.below.pop())
+    }
+
+    #[inline(always)]
+    fn peek(&self) -> Option<T> {
+        self.top
+    }
+
+    #[inline(always)]
+    fn depth(&self) -> usize {
+        self.below.len() + self.top.is_some() as usize
+    }
+
+    fn truncate(&mut self, depth: usize) {
+        if depth < self.depth() {
+            self.top = depth.checked_sub(1).map(|top| self.below[top]);
+            self.below.truncate(depth.saturating_sub(1));
+        }
+    }
+
+    fn to_vec(&self) -> Vec<T> {
+        let mut values = self.below.clone();
+        values.extend(self.top);
+        values
+    }
+}
+
+/// A stack of at most `N` values in an array, without any heap allocation. Pushing onto a full
+///   stack panics.
+pub struct ArrayStack<T, const N: usize> {
+    values: [T; N],
+    len: usize,
+}
+
+impl<T: StackValue, const N: usize> ParseStack for ArrayStack<T, N> {
+    type Value = T;
+
+    fn from_slice(values: &[T]) -> Self {
+        let mut stack = ArrayStack {
+            values: [T::from_bits(0); N],
+            len: values.len(),
+        };
+        stack.values[..values.len()].copy_from_slice(values);
+        stack
+    }
+
+    #[inline(always)]
+    fn push(&mut self, value: T) {
+        count(Event::Push);
+        assert!(self.len < N, "ArrayStack of capacity {} is full", N);
+        self.values[self.len] = value;
+        self.len += 1;
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<T> {
+        count(Event::Pop);
+        self.len = self.len.checked_sub(1)?;
+        Some(self.values[self.len])
+    }
+
+    #[inline(always)]
+    fn peek(&self) -> Option<T> {
+        self.len.checked_sub(1).map(|top| self.values[top])
+    }
+
+    #[inline(always)]
+    fn depth(&self) -> usize {
+        self.len
+    }
+
+    fn truncate(&mut self, depth: usize) {
+        self.len = self.len.min(depth);
+    }
+
+    fn to_vec(&self) -> Vec<T> {
+        self.values[..self.len].to_vec()
+    }
+}
+
+thread_local! {
+    /// Arenas of dropped `BumpStack`s, reset and ready for the next parse on this thread
+    static ARENAS: RefCell<Vec<Bump>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A stack in a bump allocator. Growing it allocates a new array in the arena and leaves the old
+///   one there until the stack is dropped, then the arena is reset and reused by the next stack on
+///   the same thread. So after a few parses there is no more allocation at all.
+pub struct BumpStack<T> {
+    arena: Bump,
+    /// An array of `capacity` values in `arena`, of which the first `len` are on the stack
+    values: NonNull<T>,
+    capacity: usize,
+    len: usize,
+}
+
+impl<T: StackValue> BumpStack<T> {
+    #[cold]
+    fn grow(&mut self) {
+        let capacity = (self.capacity * 2).max(16);
+        let values = self.arena.alloc_slice_fill_copy(capacity, T::from_bits(0));
+        values[..self.len].copy_from_slice(self.as_slice());
+        self.values = NonNull::from(values).cast();
+        self.capacity = capacity;
+    }
+
+    #[inline(always)]
+    fn put(&mut self, value: T) {
+        if self.len == self.capacity {
+            self.grow();
+        }
+        // SAFETY: `len < capacity` and `values` points to `capacity` values in `arena`
+        unsafe { self.values.as_ptr().add(self.len).write(value) };
+        self.len += 1;
+    }
+
+    fn as_slice(&self) -> &[T] {
+        // SAFETY: `values` points to `capacity >= len` initialised values in `arena`, which does
+        //   not move or free them while `self` is alive (the chunks of a `Bump` are on the heap).
+        unsafe { std::slice::from_raw_parts(self.values.as_ptr(), self.len) }
+    }
+}
+
+impl<T: StackValue> ParseStack for BumpStack<T> {
+    type Value = T;
+
+    fn from_slice(values: &[T]) -> Self {
+        let arena = ARENAS.with(|arenas| arenas.borrow_mut().pop().unwrap_or_default());
+        let mut stack = BumpStack {
+            arena,
+            values: NonNull::dangling(),
+            capacity: 0,
+            len: 0,
+        };
+        for &value in values {
+            stack.put(value);
+        }
+        stack
+    }
+
+    #[inline(always)]
+    fn push(&mut self, value: T) {
+        count(Event::Push);
+        self.put(value)
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<T> {
+        count(Event::Pop);
+        self.len = self.len.checked_sub(1)?;
+        // SAFETY: the old `len - 1 < capacity` was written by a push
+        Some(unsafe { self.values.as_ptr().add(self.len).read() })
+    }
+
+    #[inline(always)]
+    fn peek(&self) -> Option<T> {
+        self.as_slice().last().copied()
+    }
+
+    #[inline(always)]
+    fn depth(&self) -> usize {
+        self.len
+    }
+
+    fn truncate(&mut self, depth: usize) {
+        self.len = self.len.min(depth);
+    }
+
+    fn to_vec(&self) -> Vec<T> {
+        self.as_slice().to_vec()
+    }
+}
+
+impl<T> Drop for BumpStack<T> {
+    fn drop(&mut self) {
+        let mut arena = std::mem::take(&mut self.arena);
+        arena.reset();
+        // the thread local is gone when a stack is dropped during thread shutdown, then the arena
+        //   is simply freed
+        let _ = ARENAS.try_with(|arenas| arenas.borrow_mut().push(arena));
+    }
+}
+
+/// A stack packed into words, `T::BITS` bits per value
+pub struct BitStack<T> {
+    words: Vec<u64>,
+    len: usize,
+    value: PhantomData<T>,
+}
+
+impl<T: StackValue> BitStack<T> {
+    const PER_WORD: usize = (u64::BITS / T::BITS) as usize;
+    const MASK: u64 = (1 << T::BITS) - 1;
+
+    #[inline(always)]
+    fn get(&self, index: usize) -> T {
+        let shift = (index % Self::PER_WORD) as u32 * T::BITS;
+        T::from_bits((self.words[index / Self::PER_WORD] >> shift) & Self::MASK)
+    }
+
+    #[inline(always)]
+    fn put(&mut self, value: T) {
+        let (word, shift) = (self.len / Self::PER_WORD, self.len % Self::PER_WORD);
+        if word == self.words.len() {
+            self.words.push(0);
+        }
+        let shift = shift as u32 * T::BITS;
+        let bits = &mut self.words[word];
+        *bits = (*bits & !(Self::MASK << shift)) | (value.to_bits() << shift);
+        self.len += 1;
+    }
+}
+
+impl<T: StackValue> ParseStack for BitStack<T> {
+    type Value = T;
+
+    fn from_slice(values: &[T]) -> Self {
+        let mut stack = BitStack {
+            words: vec![],
+            len: 0,
+            value: PhantomData,
+        };
+        for &value in values {
+            stack.put(value);
+        }
+        stack
+    }
+
+    #[inline(always)]
+    fn push(&mut self, value: T) {
+        count(Event::Push);
+        self.put(value)
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<T> {
+        count(Event::Pop);
+        self.len = self.len.checked_sub(1)?;
+        Some(self.get(self.len))
+    }
+
+    #[inline(always)]
+    fn peek(&self) -> Option<T> {
+        self.len.checked_sub(1).map(|top| self.get(top))
+    }
+
+    #[inline(always)]
+    fn depth(&self) -> usize {
+        self.len
+    }
+
+    fn truncate(&mut self, depth: usize) {
+        self.len = self.len.min(depth);
+    }
+
+    fn to_vec(&self) -> Vec<T> {
+        (0..self.len).map(|i| self.get(i)).collect()
+    }
+}
@@ -0,0 +1,288 @@
+use crate::{bytes_left, Actions, Error, Iter, DEFAULT_MAX_DEPTH};
+use std::fmt;
+
+/// What a parser did in one iteration of its loop
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Action {
+    Reduce(&'static str),
+    Shift(char),
+    Accept,
+    Reject(Error),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Reduce(rule) => write!(f, "reduce {}", rule),
+            Action::Shift(c) => write!(f, "shift {:?}", c),
+            Action::Accept => write!(f, "accept"),
+            Action::Reject(e) => write!(f, "reject {:?}", e),
+        }
+    }
+}
+
+/// One iteration of the loop of a parser
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Step {
+    pub label: String,
+    /// State numbers from bottom to top
+    pub stack: Vec<i32>,
+    pub lookahead: Option<char>,
+    /// The number of characters consumed before this step
+    pub offset: usize,
+    pub actions: Vec<Action>,
+}
+
+/// The `Actions` that record every step of a parser, see [`trace`]
+#[derive(Clone, Debug, Default)]
+pub struct Tracer {
+    steps: Vec<Step>,
+    /// The bytes left in the input at each step
+    remaining: Vec<usize>,
+}
+
+impl Actions for Tracer {
+    fn outprod(&mut self, rule: &'static str) {
+        if let Some(step) = self.steps.last_mut() {
+            step.actions.push(Action::Reduce(rule));
+        }
+    }
+
+    const TRACE: bool = true;
+
+    fn step(&mut self, label: String, stack: Vec<i32>, lookahead: Option<char>, remaining: usize) {
+        self.steps.push(Step {
+            label,
+            stack,
+            lookahead,
+            offset: 0,
+            actions: vec![],
+        });
+        self.remaining.push(remaining);
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+    pub result: Result<(), Error>,
+}
+
+/// Runs a parser variant on the input and records a step for every iteration of its loop, e.g.
+///   `trace(optimising_recursive_ascent::paper::parse_minpush::<_, Vec<_>>, "a+a*a")`.
+///
+/// The variants only report their reductions, the shifts are found from the input that a step
+///   read: each character goes right before the first reduction of a rule that ends in it, or
+///   after the reductions of the step, but before the shifts of the characters after it. A
+///   character that a variant reads and then rejects with [`Error::Unexpected`] is not shifted.
+///   The tests of `recursive_ascent_comparison` check that this gives every variant the shifts
+///   and reductions in the same order.
+pub fn trace(parse: fn(&mut Iter, &mut Tracer, usize) -> Result<(), Error>, input: &str) -> Trace {
+    let mut iter = input.chars().peekable();
+    let mut tracer = Tracer::default();
+    let result = parse(&mut iter, &mut tracer, DEFAULT_MAX_DEPTH);
+    let Tracer {
+        mut steps,
+        mut remaining,
+    } = tracer;
+    // the input left after the last step tells us what it read
+    remaining.push(bytes_left(&mut iter));
+    let boundaries: Vec<usize> = input
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([input.len()])
+        .collect();
+    let offset = |read: usize| {
+        boundaries
+            .binary_search(&read)
+            .expect("a step should start at a character")
+    };
+    let read: Vec<usize> = remaining.iter().map(|r| input.len() - r).collect();
+    let len = steps.len();
+    for (i, step) in steps.iter_mut().enumerate() {
+        step.offset = offset(read[i]);
+        let mut shifted: Vec<char> = input[read[i]..read[i + 1]].chars().collect();
+        if i + 1 == len
+            && matches!(result, Err(Error::Unexpected(c)) if shifted.last() == Some(&c))
+        {
+            shifted.pop();
+        }
+        // from the last character back, so each shift goes as late as the reductions allow
+        let mut end = step.actions.len();
+        for c in shifted.into_iter().rev() {
+            end = step.actions[..end]
+                .iter()
+                .position(|a| matches!(a, Action::Reduce(rule) if rule.ends_with(c)))
+                .unwrap_or(end);
+            step.actions.insert(end, Action::Shift(c));
+        }
+    }
+    if let Some(last) = steps.last_mut() {
+        last.actions.push(match result {
+            Ok(()) => Action::Accept,
+            Err(e) => Action::Reject(e),
+        });
+    }
+    Trace { steps, result }
+}
+
+fn stack_string(stack: &[i32]) -> String {
+    let numbers: Vec<String> = stack.iter().map(|n| n.to_string()).collect();
+    format!("[{}]", numbers.join(", "))
+}
+
+fn lookahead_string(lookahead: Option<char>) -> String {
+    lookahead.map_or("EOF".to_string(), |c| format!("{:?}", c))
+}
+
+/// Pads every column of the rows to the widest cell in that column
+fn table(rows: &[Vec<String>]) -> String {
+    let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|c| {
+            rows.iter()
+                .filter_map(|r| r.get(c))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let mut out = String::new();
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+            .collect();
+        out.push_str(cells.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// A table with a line per step: label, stack, lookahead and actions
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = vec![vec![
+            "step".to_string(),
+            "label".to_string(),
+            "stack".to_string(),
+            "lookahead".to_string(),
+            "actions".to_string(),
+        ]];
+        for (i, step) in self.steps.iter().enumerate() {
+            let actions: Vec<String> = step.actions.iter().map(|a| a.to_string()).collect();
+            rows.push(vec![
+                i.to_string(),
+                step.label.clone(),
+                stack_string(&step.stack),
+                lookahead_string(step.lookahead),
+                actions.join("; "),
+            ]);
+        }
+        write!(f, "{}", table(&rows))
+    }
+}
+
+impl Trace {
+    /// How far along the parse is at each step: the input consumed and the reductions done
+    ///   before the step. Every variant goes through the same progress points, the steps in
+    ///   between are where they differ.
+    fn progress(&self) -> Vec<(usize, usize)> {
+        let mut reductions = 0;
+        self.steps
+            .iter()
+            .map(|step| {
+                let progress = (step.offset, reductions);
+                reductions += step
+                    .actions
+                    .iter()
+                    .filter(|a| matches!(a, Action::Reduce(_)))
+                    .count();
+                progress
+            })
+            .collect()
+    }
+}
+
+/// Lines up two runs of steps by their labels (longest common subsequence)
+fn align<'a>(left: &'a [Step], right: &'a [Step]) -> Vec<(Option<&'a Step>, Option<&'a Step>)> {
+    let mut lcs = vec![vec![0; right.len() + 1]; left.len() + 1];
+    for l in (0..left.len()).rev() {
+        for r in (0..right.len()).rev() {
+            lcs[l][r] = if left[l].label == right[r].label {
+                lcs[l + 1][r + 1] + 1
+            } else {
+                lcs[l + 1][r].max(lcs[l][r + 1])
+            };
+        }
+    }
+    let (mut l, mut r) = (0, 0);
+    let mut pairs = vec![];
+    while l < left.len() || r < right.len() {
+        if l < left.len() && r < right.len() && left[l].label == right[r].label {
+            pairs.push((Some(&left[l]), Some(&right[r])));
+            l += 1;
+            r += 1;
+        } else if r == right.len() || (l < left.len() && lcs[l + 1][r] >= lcs[l][r + 1]) {
+            pairs.push((Some(&left[l]), None));
+            l += 1;
+        } else {
+            pairs.push((None, Some(&right[r])));
+            r += 1;
+        }
+    }
+    pairs
+}
+
+/// Lines up the steps of two traces by their progress through the parse, so you can see which
+///   labels one variant goes through that the other skips. At the same progress point steps with
+///   the same label are shown side by side with `=` in the middle column, other steps are shown
+///   on their own with `<` or `>`.
+pub fn diff(left_name: &str, left: &Trace, right_name: &str, right: &Trace) -> String {
+    fn cell(step: Option<&Step>) -> String {
+        step.map_or(String::new(), |s| {
+            format!("{} {}", s.label, stack_string(&s.stack))
+        })
+    }
+
+    let (left_progress, right_progress) = (left.progress(), right.progress());
+    let mut rows = vec![vec![
+        "offset".to_string(),
+        "reductions".to_string(),
+        left_name.to_string(),
+        String::new(),
+        right_name.to_string(),
+    ]];
+    let (mut l, mut r) = (0, 0);
+    while l < left.steps.len() || r < right.steps.len() {
+        let progress = match (left_progress.get(l), right_progress.get(r)) {
+            (Some(lp), Some(rp)) => lp.min(rp),
+            (lp, rp) => lp.or(rp).expect("one of the traces has steps left"),
+        };
+        let left_end = l + left_progress[l..]
+            .iter()
+            .take_while(|&p| p == progress)
+            .count();
+        let right_end = r + right_progress[r..]
+            .iter()
+            .take_while(|&p| p == progress)
+            .count();
+        for (left_step, right_step) in align(&left.steps[l..left_end], &right.steps[r..right_end]) {
+            let marker = match (left_step, right_step) {
+                (Some(_), Some(_)) => "=",
+                (Some(_), None) => "<",
+                _ => ">",
+            };
+            rows.push(vec![
+                progress.0.to_string(),
+                progress.1.to_string(),
+                cell(left_step),
+                marker.to_string(),
+                cell(right_step),
+            ]);
+        }
+        (l, r) = (left_end, right_end);
+    }
+    table(&rows)
+}
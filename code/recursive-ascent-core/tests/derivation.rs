@@ -0,0 +1,36 @@
+use recursive_ascent_core::derivation::Tree;
+
+#[test]
+fn reductions_of_one_tree() {
+    let tree = Tree::from_reductions(&["F = a", "T = F", "E = T", "S = E"]).unwrap();
+    assert_eq!(tree.rightmost_derivation(), ["S", "E", "T", "F", "a"]);
+}
+
+#[test]
+fn reductions_that_are_not_one_tree() {
+    // nothing to reduce
+    assert_eq!(Tree::from_reductions(&[]), None);
+    // `T = F` without an `F`
+    assert_eq!(Tree::from_reductions(&["T = F"]), None);
+    // the `F` where the `T` of `E = T` should be
+    assert_eq!(Tree::from_reductions(&["F = a", "E = T"]), None);
+    // two trees left over
+    assert_eq!(Tree::from_reductions(&["F = a", "F = a"]), None);
+    // not a rule
+    assert_eq!(Tree::from_reductions(&["F a"]), None);
+}
+
+#[test]
+fn dot_has_the_terminals_on_one_rank_in_input_order() {
+    let tree = Tree::from_reductions(&["F = a", "T = F", "E = T", "F = ( E )"]).unwrap();
+    let dot = tree.to_dot();
+    assert!(
+        dot.contains(
+            "      rank=\"same\";\n      n1 [label=\"(\"];\n      n5 [label=\"a\"];\n      \
+             n6 [label=\")\"];\n"
+        ),
+        "{}",
+        dot
+    );
+    assert!(dot.contains("    n1 -- n5 -- n6;\n"), "{}", dot);
+}
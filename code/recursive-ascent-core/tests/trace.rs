@@ -0,0 +1,135 @@
+use recursive_ascent_core::trace::{trace, Action, Step, Tracer};
+use recursive_ascent_core::{step, Actions, Error, Iter};
+
+/// Parses `( a )` in a single step, like a variant that inlined everything
+fn parse_inlined(input: &mut Iter, actions: &mut Tracer, _max_depth: usize) -> Result<(), Error> {
+    let stack: Vec<i32> = vec![0];
+    step(actions, &"S0", &stack, input);
+    for expected in ['(', 'a', ')'] {
+        match input.next() {
+            Some(c) if c == expected => {}
+            Some(c) => return Err(Error::Unexpected(c)),
+            None => return Err(Error::EOF),
+        }
+        match expected {
+            'a' => actions.outprod("F = a"),
+            ')' => actions.outprod("F = ( F )"),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Parses any number of `a`s and `é`s with a step per character, and reads what else comes before
+///   it rejects it, like the `match input.next()` of the variants in `paper.rs`. Only an `é` is
+///   pushed, so long inputs of `a`s keep the snapshots small.
+fn parse_read_then_reject(
+    input: &mut Iter,
+    actions: &mut Tracer,
+    _max_depth: usize,
+) -> Result<(), Error> {
+    let mut stack: Vec<i32> = vec![0];
+    loop {
+        step(actions, &"S0", &stack, input);
+        match input.next() {
+            Some('a') => {}
+            Some('é') => stack.push(4),
+            Some(c) => return Err(Error::Unexpected(c)),
+            None => return Ok(()),
+        }
+    }
+}
+
+fn actions(steps: &[Step]) -> Vec<Vec<Action>> {
+    steps.iter().map(|s| s.actions.clone()).collect()
+}
+
+#[test]
+fn shifts_go_before_the_reductions_of_their_rules() {
+    let trace = trace(parse_inlined, "(a)");
+    assert_eq!(trace.result, Ok(()));
+    assert_eq!(
+        actions(&trace.steps),
+        [vec![
+            Action::Shift('('),
+            Action::Shift('a'),
+            Action::Reduce("F = a"),
+            Action::Shift(')'),
+            Action::Reduce("F = ( F )"),
+            Action::Accept,
+        ]]
+    );
+}
+
+#[test]
+fn a_rejected_character_is_not_shifted() {
+    let unexpected = trace(parse_inlined, "(b)");
+    assert_eq!(unexpected.result, Err(Error::Unexpected('b')));
+    assert_eq!(
+        actions(&unexpected.steps),
+        [vec![
+            Action::Shift('('),
+            Action::Reject(Error::Unexpected('b'))
+        ]]
+    );
+    let eof = trace(parse_inlined, "(a");
+    assert_eq!(
+        actions(&eof.steps),
+        [vec![
+            Action::Shift('('),
+            Action::Shift('a'),
+            Action::Reduce("F = a"),
+            Action::Reject(Error::EOF)
+        ]]
+    );
+}
+
+#[test]
+fn offsets_count_characters() {
+    let trace = trace(parse_read_then_reject, "aéa+");
+    let steps: Vec<(usize, Option<char>, Vec<i32>)> = trace
+        .steps
+        .iter()
+        .map(|s| (s.offset, s.lookahead, s.stack.clone()))
+        .collect();
+    assert_eq!(
+        steps,
+        [
+            (0, Some('a'), vec![0]),
+            (1, Some('é'), vec![0]),
+            (2, Some('a'), vec![0, 4]),
+            (3, Some('+'), vec![0, 4]),
+        ]
+    );
+    assert_eq!(
+        actions(&trace.steps),
+        [
+            vec![Action::Shift('a')],
+            vec![Action::Shift('é')],
+            vec![Action::Shift('a')],
+            vec![Action::Reject(Error::Unexpected('+'))],
+        ]
+    );
+}
+
+#[test]
+fn a_long_input() {
+    let input = "a".repeat(100_000);
+    let trace = trace(parse_read_then_reject, &input);
+    assert_eq!(trace.steps.len(), 100_001);
+    for (i, step) in trace.steps.iter().enumerate() {
+        assert_eq!(step.offset, i);
+    }
+    assert_eq!(trace.steps[100_000].actions, [Action::Accept]);
+}
+
+#[test]
+fn display() {
+    let trace = trace(parse_read_then_reject, "é+");
+    assert_eq!(
+        trace.to_string(),
+        "step  label  stack   lookahead  actions\n\
+         0     \"S0\"   [0]     'é'        shift 'é'\n\
+         1     \"S0\"   [0, 4]  '+'        reject Unexpected('+')\n"
+    );
+}
@@ -0,0 +1,666 @@
+use crate::grammar::{Grammar, Symbol};
+use crate::lr::{Action, Lookahead, Lr};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::collections::BTreeSet;
+
+/// How far a generated parser is optimised, every level includes the ones before it
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Level {
+    /// Every state is pushed, like `paper::parse` of the first blog post
+    Lr,
+    /// States that only reduce are inlined, like `parse_chain_elim` of the first blog post
+    ChainElim,
+    /// Only the states that gotos peek at are pushed, like `parse_minpush` of the second blog post
+    MinPush,
+    /// Every character is read once, like `parse_single_input_next` of the second blog post
+    SingleInputNext,
+}
+
+impl Level {
+    pub const ALL: [Level; 4] = [
+        Level::Lr,
+        Level::ChainElim,
+        Level::MinPush,
+        Level::SingleInputNext,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Lr => "lr",
+            Level::ChainElim => "chain_elim",
+            Level::MinPush => "minpush",
+            Level::SingleInputNext => "single_input_next",
+        }
+    }
+
+    /// What each level adds to the generated parser, for its doc comment
+    fn doc(self) -> &'static [&'static str] {
+        match self {
+            Level::Lr => &[
+                "One match on the label and the next character decides every step. Every state is",
+                "  pushed on the stack when it is entered. A reduction pops a state per symbol of its",
+                "  rule and jumps to the goto label of its sort, which peeks at the state on top of the",
+                "  stack to find the state to go to.",
+            ],
+            Level::ChainElim => &[
+                "States that do nothing but reduce one rule, like `F = a •` or the chain rule `T = F •`,",
+                "  are never entered: the shift or goto into them reduces right away. When that leaves",
+                "  only one state to go to, the goto is made here instead of peeking at the stack, so",
+                "  chains of reductions like `F = a`, `T = F` happen in one step.",
+            ],
+            Level::MinPush => &[
+                "Only the states where rules start are pushed, because only those are peeked at by a",
+                "  goto. A reduction pops just the states that were pushed for its rule.",
+            ],
+            Level::SingleInputNext => &[
+                "The next character is read with `input.next()` once and kept as the lookahead until it",
+                "  is shifted, instead of peeking at it in every step and reading it again to shift it,",
+                "  like `parse_single_input_next`.",
+            ],
+        }
+    }
+}
+
+/// What the generated parsers do with the reductions
+pub enum Semantics {
+    /// `actions.outprod(rule)` for every reduction, like the variants of the blog posts
+    Outprod,
+    /// Every sort has a value of type `value`, which is computed by a closure per rule from the
+    ///   values of the symbols of the rule. The value of a terminal is its character.
+    Values {
+        value: TokenStream,
+        actions: Vec<TokenStream>,
+    },
+}
+
+/// The items of a module with a parser for the grammar per function name and level: the `State`
+///   labels, the `Sort`s that have a goto label, the `StackLabel`s that are pushed, the `Parser`
+///   struct and the `Error`. With [`Semantics::Outprod`] also the `Actions` trait.
+pub fn module(
+    grammar: &Grammar,
+    parsers: &[(&str, Level)],
+    semantics: &Semantics,
+) -> Result<TokenStream, String> {
+    let lr = Lr::new(grammar)?;
+    if let Semantics::Values { actions, .. } = semantics {
+        assert_eq!(actions.len(), grammar.rules().len(), "an action per rule");
+    }
+    let mut used = Used::default();
+    let functions: Vec<TokenStream> = parsers
+        .iter()
+        .map(|&(name, level)| Variant::new(&lr, level, semantics, &mut used).function(name))
+        .collect();
+
+    let labels = used.labels.iter().map(|&state| format_ident!("S{}", state));
+    let sorts: Vec<_> = grammar
+        .sorts()
+        .into_iter()
+        .filter(|sort| used.gotos.contains(*sort))
+        .map(|sort| format_ident!("{}", sort))
+        .collect();
+    let goto = (!sorts.is_empty()).then(|| quote!(Goto(Sort),));
+    let sort = (!sorts.is_empty()).then(|| {
+        quote! {
+            #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+            pub enum Sort {
+                #(#sorts,)*
+            }
+        }
+    });
+    let stack_labels = used
+        .stack_labels
+        .iter()
+        .map(|&state| format_ident!("SL{}", state));
+    let push = used.push.then(|| {
+        quote! {
+            #[inline(always)]
+            fn push(&mut self, label: StackLabel) {
+                self.stack.push(label);
+            }
+        }
+    });
+    let pop = used.pop.then(|| {
+        quote! {
+            #[inline(always)]
+            fn pop(&mut self) {
+                self.stack.pop();
+            }
+        }
+    });
+    let peek = used.peek.then(|| {
+        quote! {
+            #[inline(always)]
+            fn peek(&self) -> StackLabel {
+                *self.stack.last().expect("the bottom of the stack is never popped")
+            }
+        }
+    });
+    let (values, value_field, value_default, value_pop, actions_trait) = match semantics {
+        Semantics::Values { value, .. } => (
+            quote!(pub type Value = #value;),
+            quote!(values: Vec<Value>,),
+            quote!(values: vec![],),
+            quote! {
+                #[inline(always)]
+                fn value(&mut self) -> Value {
+                    self.values.pop().expect("a value for every sort that was reduced")
+                }
+            },
+            quote!(),
+        ),
+        Semantics::Outprod => (
+            quote!(),
+            quote!(),
+            quote!(),
+            quote!(),
+            quote! {
+                pub type Iter<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+                #[doc = " The semantic actions of a parser run. `outprod` is called once per reduction with the rule"]
+                #[doc = "   that was reduced, so the sequence of calls is a rightmost derivation in reverse."]
+                pub trait Actions {
+                    fn outprod(&mut self, rule: &'static str);
+                }
+
+                #[doc = " No semantic actions, but still a real call like the `()` actions of the blog posts"]
+                impl Actions for () {
+                    #[inline(never)]
+                    fn outprod(&mut self, _rule: &'static str) {}
+                }
+
+                #[doc = " Records the reductions"]
+                impl Actions for Vec<&'static str> {
+                    fn outprod(&mut self, rule: &'static str) {
+                        self.push(rule)
+                    }
+                }
+            },
+        ),
+    };
+
+    Ok(quote! {
+        #values
+
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        pub enum State {
+            #(#labels,)*
+            #goto
+        }
+
+        #sort
+
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        pub enum StackLabel {
+            #(#stack_labels,)*
+        }
+
+        #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+        #[allow(clippy::upper_case_acronyms)]
+        pub enum Error {
+            EOF,
+            Unexpected(char),
+        }
+
+        #actions_trait
+
+        pub struct Parser {
+            #[doc = " Never empty, the bottom is `SL0`"]
+            stack: Vec<StackLabel>,
+            #value_field
+            pub label: State,
+        }
+
+        impl Default for Parser {
+            fn default() -> Self {
+                Parser {
+                    stack: vec![StackLabel::SL0],
+                    #value_default
+                    label: State::S0,
+                }
+            }
+        }
+
+        impl Parser {
+            #push
+            #pop
+            #peek
+            #value_pop
+        }
+
+        #(#functions)*
+    })
+}
+
+/// What the parsers of a module use, so the module has no unused items
+#[derive(Default)]
+struct Used {
+    labels: BTreeSet<usize>,
+    gotos: BTreeSet<String>,
+    stack_labels: BTreeSet<usize>,
+    push: bool,
+    pop: bool,
+    peek: bool,
+}
+
+/// Reductions that lead to more reductions with a known state to go to are followed this deep in
+///   the generated code, after that the goto label is used
+const MAX_INLINE_DEPTH: usize = 16;
+
+/// The generation of the parser function of one level
+struct Variant<'a, 'g> {
+    lr: &'a Lr<'g>,
+    level: Level,
+    semantics: &'a Semantics,
+    /// Whether entering a state pushes it
+    pushed: Vec<bool>,
+    /// When the states where rules start are not enough to tell how many to pop, see `pop_count`
+    min_push_fallback: bool,
+    /// The labels that are jumped to and still need their arms
+    todo: Vec<usize>,
+    labels: BTreeSet<usize>,
+    gotos: BTreeSet<&'g str>,
+    used: &'a mut Used,
+}
+
+impl<'a, 'g> Variant<'a, 'g> {
+    fn new(lr: &'a Lr<'g>, level: Level, semantics: &'a Semantics, used: &'a mut Used) -> Self {
+        let states = lr.lr0.states.len();
+        let mut variant = Variant {
+            lr,
+            level,
+            semantics,
+            pushed: (0..states)
+                .map(|state| !variant_inlines(lr, level, state))
+                .collect(),
+            min_push_fallback: false,
+            todo: vec![0],
+            labels: BTreeSet::from([0]),
+            gotos: BTreeSet::new(),
+            used,
+        };
+        if level >= Level::MinPush {
+            let all = std::mem::replace(
+                &mut variant.pushed,
+                (0..states)
+                    .map(|state| state == 0 || lr.lr0.predicts(state))
+                    .collect(),
+            );
+            if !variant.pop_counts_are_fixed() {
+                variant.pushed = all;
+                variant.min_push_fallback = true;
+            }
+        }
+        variant.used.labels.insert(0);
+        variant.used.stack_labels.insert(0);
+        variant
+    }
+
+    fn inlines(&self, state: usize) -> bool {
+        variant_inlines(self.lr, self.level, state)
+    }
+
+    /// Whether every reduction pops the same number of pushed states on every path to it
+    fn pop_counts_are_fixed(&self) -> bool {
+        (0..self.lr.lr0.states.len()).all(|state| {
+            self.lr.lr0.states[state].iter().all(|item| {
+                item.dot < self.lr.lr0.grammar.rules()[item.rule].rhs.len()
+                    || item.rule == 0
+                    || self
+                        .lr
+                        .lr0
+                        .paths(state, item.dot)
+                        .windows(2)
+                        .all(|pair| self.pop_count(&pair[0]) == self.pop_count(&pair[1]))
+            })
+        })
+    }
+
+    /// The pushed states of the path of a reduction, after the state it goes back to
+    fn pop_count(&self, path: &[usize]) -> usize {
+        path[1..]
+            .iter()
+            .filter(|&&state| self.pushed[state])
+            .count()
+    }
+
+    fn function(mut self, name: &str) -> TokenStream {
+        // the arms of labels can jump to gotos, and gotos to new labels
+        let mut arms = vec![];
+        let mut goto_arms = vec![];
+        let mut done = BTreeSet::new();
+        loop {
+            while let Some(state) = self.todo.pop() {
+                arms.push((state, self.state_arm(state)));
+            }
+            let pending: Vec<&str> = self.gotos.difference(&done).copied().collect();
+            if pending.is_empty() {
+                break;
+            }
+            for sort in pending {
+                done.insert(sort);
+                goto_arms.push(self.goto_arm(sort));
+            }
+        }
+        arms.sort_by_key(|&(state, _)| state);
+        let arms = arms.into_iter().map(|(_, arm)| arm);
+        let goto_order: Vec<&str> = self
+            .lr
+            .lr0
+            .grammar
+            .sorts()
+            .into_iter()
+            .filter(|sort| done.contains(sort))
+            .collect();
+        goto_arms.sort_by_key(|(sort, _)| goto_order.iter().position(|s| s == sort));
+        let goto_arms = goto_arms.into_iter().map(|(_, arm)| arm);
+
+        for &state in &self.labels {
+            self.used.labels.insert(state);
+        }
+        for sort in &self.gotos {
+            self.used.gotos.insert(sort.to_string());
+        }
+
+        let mut doc: Vec<String> = vec![];
+        for level in Level::ALL.into_iter().filter(|&level| level <= self.level) {
+            if !doc.is_empty() {
+                doc.push(String::new());
+            }
+            doc.extend(level.doc().iter().map(|line| line.to_string()));
+        }
+        if self.min_push_fallback {
+            doc.push(String::new());
+            doc.push(
+                "The states where rules start are not enough to tell how many states a".to_string(),
+            );
+            doc.push(
+                "  reduction pops in this grammar, so every state is pushed after all.".to_string(),
+            );
+        }
+        let doc = doc.iter().map(|line| {
+            let line = if line.is_empty() {
+                String::new()
+            } else {
+                format!(" {}", line)
+            };
+            quote!(#[doc = #line])
+        });
+        let name = format_ident!("{}", name);
+        let (read, next) = if self.level >= Level::SingleInputNext {
+            (quote!(let mut lookahead = input.next();), quote!(lookahead))
+        } else {
+            (quote!(), quote!(input.peek().copied()))
+        };
+        let body = quote! {
+            let mut p = Parser::default();
+            #read
+            loop {
+                match (p.label, #next) {
+                    #(#arms)*
+                    #(#goto_arms)*
+                    (_, Some(c)) => return Err(Error::Unexpected(c)),
+                    (_, None) => return Err(Error::EOF),
+                }
+            }
+        };
+        match self.semantics {
+            Semantics::Outprod => quote! {
+                #(#doc)*
+                pub fn #name<A: Actions>(input: &mut Iter, actions: &mut A) -> Result<(), Error> {
+                    #body
+                }
+            },
+            Semantics::Values { .. } => quote! {
+                #(#doc)*
+                pub fn #name(input: &str) -> Result<Value, Error> {
+                    let input = &mut input.chars().peekable();
+                    #body
+                }
+            },
+        }
+    }
+
+    /// The arms of a label for every lookahead, with the same code under one pattern
+    fn state_arm(&mut self, state: usize) -> TokenStream {
+        let mut groups: Vec<(String, TokenStream, Vec<Lookahead>)> = vec![];
+        for (&lookahead, &action) in &self.lr.actions[state] {
+            let code = match action {
+                Action::Shift(target) => {
+                    let enter = self.enter(target, state, 0);
+                    let read = if self.level >= Level::SingleInputNext {
+                        quote!(lookahead = input.next();)
+                    } else {
+                        quote!(let _ = input.next();)
+                    };
+                    quote! {
+                        #read
+                        #enter
+                    }
+                }
+                Action::Reduce(rule) => {
+                    let n = self.lr.lr0.grammar.rules()[rule].rhs.len();
+                    let paths = self.lr.lr0.paths(state, n);
+                    self.reduce(rule, &paths, 0)
+                }
+                Action::Accept => self.reduce(0, &[], 0),
+            };
+            let key = code.to_string();
+            match groups.iter_mut().find(|(k, _, _)| *k == key) {
+                Some((_, _, lookaheads)) => lookaheads.push(lookahead),
+                None => groups.push((key, code, vec![lookahead])),
+            }
+        }
+        let label = format_ident!("S{}", state);
+        let arms = groups.into_iter().map(|(_, code, lookaheads)| {
+            let chars: Vec<char> = lookaheads.iter().flatten().copied().collect();
+            let mut patterns = vec![];
+            if !chars.is_empty() {
+                patterns.push(quote!(Some(#(#chars)|*)));
+            }
+            if lookaheads.contains(&None) {
+                patterns.push(quote!(None));
+            }
+            quote! {
+                (State::#label, #(#patterns)|*) => {
+                    #code
+                }
+            }
+        });
+        quote!(#(#arms)*)
+    }
+
+    /// The arm of the goto label of a sort, that peeks at the state to go back to
+    fn goto_arm(&mut self, sort: &'g str) -> (&'g str, TokenStream) {
+        let symbol = self
+            .lr
+            .lr0
+            .grammar
+            .rules()
+            .iter()
+            .flat_map(|rule| &rule.rhs)
+            .find(|symbol| matches!(symbol, Symbol::Sort(s) if s == sort))
+            .expect("a sort with a goto label is used in a rule");
+        let mut groups: Vec<(String, TokenStream, Vec<usize>)> = vec![];
+        for origin in 0..self.lr.lr0.states.len() {
+            if let Some(target) = self.lr.lr0.goto(origin, symbol) {
+                let code = self.enter(target, origin, 0);
+                let key = code.to_string();
+                match groups.iter_mut().find(|(k, _, _)| *k == key) {
+                    Some((_, _, origins)) => origins.push(origin),
+                    None => groups.push((key, code, vec![origin])),
+                }
+            }
+        }
+        // the most common code goes last, for any other state
+        groups.sort_by_key(|(_, _, origins)| std::cmp::Reverse(origins.len()));
+        let sort_ident = format_ident!("{}", sort);
+        let code = match &groups[..] {
+            [(_, code, _)] => code.clone(),
+            [.., (_, last, _)] => {
+                self.used.peek = true;
+                let arms = groups[..groups.len() - 1].iter().map(|(_, code, origins)| {
+                    let labels = origins.iter().map(|&origin| {
+                        self.used.stack_labels.insert(origin);
+                        let label = format_ident!("SL{}", origin);
+                        quote!(StackLabel::#label)
+                    });
+                    quote!(#(#labels)|* => { #code })
+                });
+                quote! {
+                    match p.peek() {
+                        #(#arms)*
+                        _ => { #last }
+                    }
+                }
+            }
+            [] => unreachable!("a sort with a goto label has a goto"),
+        };
+        (
+            sort,
+            quote! {
+                (State::Goto(Sort::#sort_ident), _) => {
+                    #code
+                }
+            },
+        )
+    }
+
+    /// Enters `state` from `from`: a label jump, or the reduction of a state that is inlined
+    fn enter(&mut self, state: usize, from: usize, depth: usize) -> TokenStream {
+        if self.inlines(state) && depth < MAX_INLINE_DEPTH {
+            let rule = self
+                .lr
+                .lr0
+                .only_reduction(state)
+                .expect("only states that only reduce are inlined");
+            let n = self.lr.lr0.grammar.rules()[rule].rhs.len();
+            let paths: Vec<Vec<usize>> = self
+                .lr
+                .lr0
+                .paths(state, n)
+                .into_iter()
+                .filter(|path| path[n - 1] == from)
+                .collect();
+            return self.reduce(rule, &paths, depth + 1);
+        }
+        if self.labels.insert(state) {
+            self.todo.push(state);
+        }
+        let label = format_ident!("S{}", state);
+        if self.pushed[state] {
+            self.used.push = true;
+            self.used.stack_labels.insert(state);
+            let stack_label = format_ident!("SL{}", state);
+            quote! {
+                p.push(StackLabel::#stack_label);
+                p.label = State::#label;
+            }
+        } else {
+            quote!(p.label = State::#label;)
+        }
+    }
+
+    /// Reduces a rule at the end of one of the paths, pops its pushed states and goes to the state
+    ///   at the start of the path if that is known, or the goto label of its sort otherwise
+    fn reduce(&mut self, rule: usize, paths: &[Vec<usize>], depth: usize) -> TokenStream {
+        let semantics = self.semantic_action(rule);
+        if rule == 0 {
+            return semantics;
+        }
+        let pops = self.pop_count(&paths[0]);
+        let pop = (0..pops).map(|_| quote!(p.pop();));
+        if pops > 0 {
+            self.used.pop = true;
+        }
+        let origins: BTreeSet<usize> = paths.iter().map(|path| path[0]).collect();
+        let lhs = &self.lr.lr0.grammar.rules()[rule].lhs;
+        let next = match origins.iter().collect::<Vec<_>>()[..] {
+            [&origin] if self.level >= Level::ChainElim => {
+                let symbol = self
+                    .lr
+                    .lr0
+                    .grammar
+                    .rules()
+                    .iter()
+                    .flat_map(|rule| &rule.rhs)
+                    .find(|symbol| matches!(symbol, Symbol::Sort(s) if s == lhs))
+                    .expect("a sort other than the start sort is used in a rule");
+                let target = self
+                    .lr
+                    .lr0
+                    .goto(origin, symbol)
+                    .expect("the goto of the reduced sort");
+                self.enter(target, origin, depth)
+            }
+            _ => {
+                self.gotos.insert(lhs);
+                let sort = format_ident!("{}", lhs);
+                quote!(p.label = State::Goto(Sort::#sort);)
+            }
+        };
+        quote! {
+            #(#pop)*
+            #semantics
+            #next
+        }
+    }
+
+    /// The semantic action of a rule, which returns the result for the start rule
+    fn semantic_action(&self, rule: usize) -> TokenStream {
+        let r = &self.lr.lr0.grammar.rules()[rule];
+        match self.semantics {
+            Semantics::Outprod => {
+                let rule_string = r.to_string();
+                if rule == 0 {
+                    quote! {
+                        actions.outprod(#rule_string);
+                        return Ok(());
+                    }
+                } else {
+                    quote!(actions.outprod(#rule_string);)
+                }
+            }
+            Semantics::Values { actions, .. } => {
+                let action = &actions[rule];
+                let types = r.rhs.iter().map(|symbol| match symbol {
+                    Symbol::Sort(_) => quote!(Value),
+                    Symbol::Terminal(_) => quote!(char),
+                });
+                let names: Vec<_> = (0..r.rhs.len()).map(|i| format_ident!("v{}", i)).collect();
+                let values =
+                    r.rhs
+                        .iter()
+                        .zip(&names)
+                        .rev()
+                        .filter_map(|(symbol, name)| match symbol {
+                            Symbol::Sort(_) => Some(quote!(let #name = p.value();)),
+                            Symbol::Terminal(_) => None,
+                        });
+                let arguments = r.rhs.iter().zip(&names).map(|(symbol, name)| match symbol {
+                    Symbol::Sort(_) => quote!(#name),
+                    Symbol::Terminal(c) => quote!(#c),
+                });
+                let call = quote! {
+                    #(#values)*
+                    let action: fn(#(#types),*) -> Value = #action;
+                };
+                if rule == 0 {
+                    quote! {
+                        #call
+                        return Ok(action(#(#arguments),*));
+                    }
+                } else {
+                    quote! {
+                        #call
+                        p.values.push(action(#(#arguments),*));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn variant_inlines(lr: &Lr, level: Level, state: usize) -> bool {
+    level >= Level::ChainElim && lr.lr0.only_reduction(state).is_some()
+}
@@ -0,0 +1,12 @@
+//! Generates recursive ascent parsers like the ones of the blog posts for any LALR(1) grammar,
+//!   with the optimisations of the posts applied up to a [`Level`]. The `grammar!` macro of
+//!   `recursive_ascent_macro` is built on this, and [`build`] writes the parsers for a `.grammar`
+//!   file from a build script.
+
+mod emit;
+mod lr;
+mod source;
+
+pub use emit::{module, Level, Semantics};
+pub use recursive_ascent_core::grammar;
+pub use source::{build, generate};
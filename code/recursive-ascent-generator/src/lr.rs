@@ -0,0 +1,194 @@
+use crate::grammar::{Grammar, Symbol};
+use recursive_ascent_core::lr0::{Item, Lr0};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// The next character of the input, `None` at the end of the input
+pub(crate) type Lookahead = Option<char>;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Action {
+    Shift(usize),
+    Reduce(usize),
+    Accept,
+}
+
+/// The LR(0) automaton of a grammar with LALR(1) lookaheads
+pub(crate) struct Lr<'g> {
+    pub lr0: Lr0<'g>,
+    /// The lookaheads of every item of every state
+    lookaheads: Vec<Vec<BTreeSet<Lookahead>>>,
+    pub actions: Vec<BTreeMap<Lookahead, Action>>,
+}
+
+impl<'g> Lr<'g> {
+    /// Fails with a description of the first conflict if the grammar is not LALR(1)
+    pub fn new(grammar: &'g Grammar) -> Result<Lr<'g>, String> {
+        let mut lr = Lr {
+            lr0: Lr0::new(grammar),
+            lookaheads: vec![],
+            actions: vec![],
+        };
+        lr.lookaheads = lr.lalr();
+        lr.actions = (0..lr.lr0.states.len())
+            .map(|state| lr.state_actions(state))
+            .collect::<Result<_, _>>()?;
+        Ok(lr)
+    }
+
+    /// Which sorts can derive the empty string, and the terminals that the sentences derived from
+    ///   every sort can start with
+    fn first_sets(&self) -> (HashSet<&'g str>, HashMap<&'g str, BTreeSet<char>>) {
+        let mut nullable = HashSet::new();
+        let mut first: HashMap<&str, BTreeSet<char>> = HashMap::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for rule in self.lr0.grammar.rules() {
+                let mut all_nullable = true;
+                for symbol in &rule.rhs {
+                    let (symbol_first, symbol_nullable) = match symbol {
+                        Symbol::Terminal(c) => (BTreeSet::from([*c]), false),
+                        Symbol::Sort(sort) => (
+                            first.get(sort.as_str()).cloned().unwrap_or_default(),
+                            nullable.contains(sort.as_str()),
+                        ),
+                    };
+                    let lhs = first.entry(rule.lhs.as_str()).or_default();
+                    for c in symbol_first {
+                        changed |= lhs.insert(c);
+                    }
+                    if !symbol_nullable {
+                        all_nullable = false;
+                        break;
+                    }
+                }
+                if all_nullable {
+                    changed |= nullable.insert(rule.lhs.as_str());
+                }
+            }
+        }
+        (nullable, first)
+    }
+
+    /// The lookaheads of the LR(1) items with the same cores merged, as the fixpoint of spreading
+    ///   them through the closures and the transitions
+    fn lalr(&self) -> Vec<Vec<BTreeSet<Lookahead>>> {
+        let (nullable, first) = self.first_sets();
+        let mut lookaheads: Vec<Vec<BTreeSet<Lookahead>>> = self
+            .lr0
+            .states
+            .iter()
+            .map(|items| vec![BTreeSet::new(); items.len()])
+            .collect();
+        lookaheads[0][0].insert(None);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (state, items) in self.lr0.states.iter().enumerate() {
+                for (i, &item) in items.iter().enumerate() {
+                    let Some(symbol) = self.lr0.next_symbol(item) else {
+                        continue;
+                    };
+                    let current = lookaheads[state][i].clone();
+                    if let Symbol::Sort(sort) = symbol {
+                        // what can follow the sort in this item
+                        let mut follow = BTreeSet::new();
+                        let mut rest_nullable = true;
+                        for symbol in &self.lr0.rule(item).rhs[item.dot + 1..] {
+                            match symbol {
+                                Symbol::Terminal(c) => {
+                                    follow.insert(Some(*c));
+                                    rest_nullable = false;
+                                }
+                                Symbol::Sort(s) => {
+                                    let s = s.as_str();
+                                    follow.extend(
+                                        first.get(s).into_iter().flatten().map(|&c| Some(c)),
+                                    );
+                                    rest_nullable = nullable.contains(s);
+                                }
+                            }
+                            if !rest_nullable {
+                                break;
+                            }
+                        }
+                        if rest_nullable {
+                            follow.extend(current.iter().copied());
+                        }
+                        for (j, &other) in items.iter().enumerate() {
+                            if other.dot == 0 && &self.lr0.rule(other).lhs == sort {
+                                for &lookahead in &follow {
+                                    changed |= lookaheads[state][j].insert(lookahead);
+                                }
+                            }
+                        }
+                    }
+                    let target = self
+                        .lr0
+                        .goto(state, symbol)
+                        .expect("every symbol after a dot is read");
+                    let next = Item {
+                        rule: item.rule,
+                        dot: item.dot + 1,
+                    };
+                    let j = self.lr0.states[target]
+                        .iter()
+                        .position(|&item| item == next)
+                        .expect("the target of a transition has the item moved past the symbol");
+                    for lookahead in current {
+                        changed |= lookaheads[target][j].insert(lookahead);
+                    }
+                }
+            }
+        }
+        lookaheads
+    }
+
+    fn state_actions(&self, state: usize) -> Result<BTreeMap<Lookahead, Action>, String> {
+        let mut actions = BTreeMap::new();
+        for (i, &item) in self.lr0.states[state].iter().enumerate() {
+            let new: Vec<(Lookahead, Action)> = match self.lr0.next_symbol(item) {
+                Some(symbol @ Symbol::Terminal(c)) => {
+                    vec![(
+                        Some(*c),
+                        Action::Shift(
+                            self.lr0
+                                .goto(state, symbol)
+                                .expect("a terminal after a dot is read"),
+                        ),
+                    )]
+                }
+                Some(Symbol::Sort(_)) => vec![],
+                None if item.rule == 0 => vec![(None, Action::Accept)],
+                None => self.lookaheads[state][i]
+                    .iter()
+                    .map(|&lookahead| (lookahead, Action::Reduce(item.rule)))
+                    .collect(),
+            };
+            for (lookahead, action) in new {
+                match actions.insert(lookahead, action) {
+                    Some(old) if old != action => {
+                        return Err(format!(
+                            "the grammar is not LALR(1), in state {} on {} {} conflicts with {}",
+                            state,
+                            lookahead
+                                .map_or("the end of the input".to_string(), |c| format!("{:?}", c)),
+                            self.action_string(old),
+                            self.action_string(action)
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(actions)
+    }
+
+    fn action_string(&self, action: Action) -> String {
+        match action {
+            Action::Shift(_) => "shifting".to_string(),
+            Action::Reduce(rule) => format!("reducing {}", self.lr0.grammar.rules()[rule]),
+            Action::Accept => "accepting".to_string(),
+        }
+    }
+}
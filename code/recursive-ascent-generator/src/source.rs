@@ -0,0 +1,70 @@
+use crate::emit::{module, Level, Semantics};
+use crate::grammar::Grammar;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::{env, fs};
+
+/// The Rust source of a module with a parser for the grammar per function name and level, like
+///   [`module`] with [`Semantics::Outprod`], formatted by prettyplease and rustfmt
+pub fn generate(grammar: &Grammar, parsers: &[(&str, Level)]) -> Result<String, String> {
+    let tokens = module(grammar, parsers, &Semantics::Outprod)?;
+    let file: syn::File = syn::parse2(tokens).map_err(|e| e.to_string())?;
+    let mut source = String::from("// Generated by recursive_ascent_generator from the grammar\n");
+    for rule in grammar.rules() {
+        source.push_str(&format!("//   {}\n", rule));
+    }
+    for item in file.items {
+        source.push('\n');
+        source.push_str(&prettyplease::unparse(&syn::File {
+            shebang: None,
+            attrs: vec![],
+            items: vec![item],
+        }));
+    }
+    Ok(rustfmt(&source).unwrap_or(source))
+}
+
+/// prettyplease breaks some lines differently from rustfmt, so the source goes through rustfmt
+///   when it is installed (`$RUSTFMT` or on the `PATH`)
+fn rustfmt(source: &str) -> Option<String> {
+    let rustfmt = env::var_os("RUSTFMT").unwrap_or_else(|| "rustfmt".into());
+    let mut child = Command::new(rustfmt)
+        .args(["--edition", "2021", "--emit", "stdout", "--quiet"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    child.stdin.take()?.write_all(source.as_bytes()).ok()?;
+    let output = child.wait_with_output().ok()?;
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+/// For `build.rs`: reads the `.grammar` file (see [`Grammar::parse`]) and writes the parsers to a
+///   file with the same name and the extension `.rs` in `OUT_DIR`, which is returned. Cargo reruns
+///   the build script when the grammar file changes. The module is included with
+///
+/// ```ignore
+/// mod arithmetic {
+///     include!(concat!(env!("OUT_DIR"), "/arithmetic.rs"));
+/// }
+/// ```
+pub fn build(grammar: impl AsRef<Path>, parsers: &[(&str, Level)]) -> Result<PathBuf, String> {
+    let path = grammar.as_ref();
+    println!("cargo:rerun-if-changed={}", path.display());
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let grammar = Grammar::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let source = generate(&grammar, parsers).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let out_dir = env::var_os("OUT_DIR").ok_or("OUT_DIR is not set, is this a build script?")?;
+    let stem = path
+        .file_stem()
+        .ok_or_else(|| format!("{} is not a file", path.display()))?;
+    let out = Path::new(&out_dir).join(stem).with_extension("rs");
+    fs::write(&out, source).map_err(|e| format!("{}: {}", out.display(), e))?;
+    Ok(out)
+}
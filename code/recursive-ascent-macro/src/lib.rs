@@ -0,0 +1,143 @@
+//! `grammar!` declares a grammar with a semantic action per rule and expands to a module with an
+//!   optimised recursive ascent parser for it, see [`grammar!`].
+
+use proc_macro::TokenStream;
+use quote::quote;
+use recursive_ascent_generator::grammar::{Grammar, Rule, Symbol};
+use recursive_ascent_generator::{Level, Semantics};
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Attribute, ExprClosure, Ident, LitStr, Token, Type, Visibility};
+
+/// A module with a `parse(input: &str) -> Result<Value, Error>` function for an LALR(1) grammar,
+///   generated with every optimisation of the blog posts: one match on the label and the next
+///   character per step, the chain rules and the states that only reduce inlined, only the states
+///   that gotos peek at pushed, and like `parse_single_input_next` every character read once with
+///   `input.next()`. The module also has the `State` and `StackLabel` enums and the `Parser` of
+///   the grammar.
+///
+/// The first line names the module and the type of the values of the sorts. Every rule after it
+///   is a sort, `=`, the symbols of the rule and a closure that computes the value of the rule
+///   from the values of its symbols, where the value of a terminal is its `char`. Sorts are
+///   identifiers and terminals are strings of one character. The first rule is the start rule,
+///   its value is the result of `parse`.
+///
+/// ```
+/// recursive_ascent_macro::grammar! {
+///     mod arithmetic: i64;
+///     S = E => |e| e;
+///     E = E "+" T => |e, _, t| e + t;
+///     E = T => |t| t;
+///     T = T "*" F => |t, _, f| t * f;
+///     T = F => |f| f;
+///     F = "a" => |_| 2;
+///     F = "(" E ")" => |_, e, _| e;
+/// }
+///
+/// assert_eq!(arithmetic::parse("a+a*(a+a)"), Ok(10));
+/// assert_eq!(arithmetic::parse("a+"), Err(arithmetic::Error::EOF));
+/// ```
+///
+/// The closures are compiled in the module, which has `use super::*`. A grammar that is not
+///   LALR(1) is a compile error that names the conflict.
+#[proc_macro]
+pub fn grammar(input: TokenStream) -> TokenStream {
+    let definition = parse_macro_input!(input as Definition);
+    match definition.expand() {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+struct Definition {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    value: Type,
+    rules: Vec<(Rule, ExprClosure)>,
+}
+
+impl Parse for Definition {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![mod]>()?;
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let value = input.parse()?;
+        input.parse::<Token![;]>()?;
+        let mut rules = vec![];
+        while !input.is_empty() {
+            let lhs: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let mut rhs = vec![];
+            while !input.peek(Token![=>]) {
+                if input.peek(LitStr) {
+                    let terminal: LitStr = input.parse()?;
+                    let value = terminal.value();
+                    let mut chars = value.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => rhs.push(Symbol::Terminal(c)),
+                        _ => {
+                            return Err(syn::Error::new(
+                                terminal.span(),
+                                "a terminal should be one character",
+                            ))
+                        }
+                    }
+                } else {
+                    let sort: Ident = input.parse()?;
+                    rhs.push(Symbol::Sort(sort.to_string()));
+                }
+            }
+            input.parse::<Token![=>]>()?;
+            let action: ExprClosure = input.parse()?;
+            if action.inputs.len() != rhs.len() {
+                return Err(syn::Error::new_spanned(
+                    &action.inputs,
+                    format!(
+                        "the closure should take {} values, one per symbol of the rule",
+                        rhs.len()
+                    ),
+                ));
+            }
+            input.parse::<Token![;]>()?;
+            let lhs = lhs.to_string();
+            rules.push((Rule { lhs, rhs }, action));
+        }
+        Ok(Definition {
+            attrs,
+            vis,
+            name,
+            value,
+            rules,
+        })
+    }
+}
+
+impl Definition {
+    fn expand(self) -> syn::Result<proc_macro2::TokenStream> {
+        let (rules, actions): (Vec<Rule>, Vec<ExprClosure>) = self.rules.into_iter().unzip();
+        let span = self.name.span();
+        let grammar = Grammar::new(rules).map_err(|e| syn::Error::new(span, e))?;
+        let value = &self.value;
+        let semantics = Semantics::Values {
+            value: quote!(#value),
+            actions: actions.iter().map(|action| quote!(#action)).collect(),
+        };
+        let items =
+            recursive_ascent_generator::module(&grammar, &[("parse", Level::SingleInputNext)], &semantics)
+                .map_err(|e| syn::Error::new(span, e))?;
+        let Definition {
+            attrs, vis, name, ..
+        } = self;
+        Ok(quote! {
+            #(#attrs)*
+            #vis mod #name {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #items
+            }
+        })
+    }
+}
@@ -0,0 +1,55 @@
+// Generated by binary_string from a DFA with 5 states, in the Functions style
+
+pub fn accepts(inputs: &[Input]) -> bool {
+  accepts0(inputs)
+}
+
+fn accepts0(inputs: &[Input]) -> bool {
+  match inputs.split_first() {
+    None => false,
+    Some((&input, inputs)) => match input {
+      ONE  => accepts1(inputs),
+      ZERO => false,
+    },
+  }
+}
+
+fn accepts1(inputs: &[Input]) -> bool {
+  match inputs.split_first() {
+    None => false,
+    Some((&input, inputs)) => match input {
+      ZERO => accepts2(inputs),
+      ONE  => false,
+    },
+  }
+}
+
+fn accepts2(inputs: &[Input]) -> bool {
+  match inputs.split_first() {
+    None => false,
+    Some((&input, inputs)) => match input {
+      ZERO => accepts3(inputs),
+      ONE  => false,
+    },
+  }
+}
+
+fn accepts3(inputs: &[Input]) -> bool {
+  match inputs.split_first() {
+    None => false,
+    Some((&input, inputs)) => match input {
+      ZERO => accepts3(inputs),
+      ONE  => accepts4(inputs),
+    },
+  }
+}
+
+fn accepts4(inputs: &[Input]) -> bool {
+  match inputs.split_first() {
+    None => true,
+    Some((&input, inputs)) => match input {
+      ONE  => accepts4(inputs),
+      ZERO => false,
+    },
+  }
+}
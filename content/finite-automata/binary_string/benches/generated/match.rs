@@ -0,0 +1,17 @@
+// Generated by binary_string from a DFA with 5 states, in the Match style
+
+pub fn accepts(inputs: &[Input]) -> bool {
+  let mut state = 0;
+  for &input in inputs {
+    state = match (state, input) {
+      (0, ONE)  => 1,
+      (1, ZERO) => 2,
+      (2, ZERO) => 3,
+      (3, ZERO) => 3,
+      (3, ONE)  => 4,
+      (4, ONE)  => 4,
+      _         => return false,
+    };
+  }
+  state == 4
+}
@@ -0,0 +1,68 @@
+// Generated by binary_string from a DFA with 5 states, in the Trampoline style
+
+pub enum Bounce<'a> {
+  Next(State<'a>, &'a [Input]),
+  Done(bool),
+}
+
+pub type State<'a> = fn(&'a [Input]) -> Bounce<'a>;
+
+pub fn accepts(inputs: &[Input]) -> bool {
+  let mut bounce = Bounce::Next(accepts0, inputs);
+  loop {
+    match bounce {
+      Bounce::Next(state, inputs) => bounce = state(inputs),
+      Bounce::Done(accepted) => return accepted,
+    }
+  }
+}
+
+fn accepts0(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(false),
+    Some((&input, inputs)) => match input {
+      ONE  => Bounce::Next(accepts1, inputs),
+      ZERO => Bounce::Done(false),
+    },
+  }
+}
+
+fn accepts1(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(false),
+    Some((&input, inputs)) => match input {
+      ZERO => Bounce::Next(accepts2, inputs),
+      ONE  => Bounce::Done(false),
+    },
+  }
+}
+
+fn accepts2(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(false),
+    Some((&input, inputs)) => match input {
+      ZERO => Bounce::Next(accepts3, inputs),
+      ONE  => Bounce::Done(false),
+    },
+  }
+}
+
+fn accepts3(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(false),
+    Some((&input, inputs)) => match input {
+      ZERO => Bounce::Next(accepts3, inputs),
+      ONE  => Bounce::Next(accepts4, inputs),
+    },
+  }
+}
+
+fn accepts4(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(true),
+    Some((&input, inputs)) => match input {
+      ONE  => Bounce::Next(accepts4, inputs),
+      ZERO => Bounce::Done(false),
+    },
+  }
+}
@@ -0,0 +1,92 @@
+#[macro_use]
+extern crate criterion;
+extern crate binary_string;
+
+use criterion::{BenchmarkId, Criterion, Throughput};
+
+use binary_string::{language, Input, ONE, ZERO};
+
+// The sources are `cargo run --bin generate -- functions`, `-- trampoline` and `-- match`,
+//   `tests/codegen.rs` checks that they are up to date
+mod functions {
+  use binary_string::{Input, ONE, ZERO};
+  include!("generated/functions.rs");
+}
+
+mod trampoline {
+  use binary_string::{Input, ONE, ZERO};
+  include!("generated/trampoline.rs");
+}
+
+mod table_match {
+  use binary_string::{Input, ONE, ZERO};
+  include!("generated/match.rs");
+}
+
+#[allow(dead_code)]
+#[path = "../src/implicit_state.rs"]
+mod implicit_state;
+
+type Accepts<'a> = &'a dyn Fn(&[Input]) -> bool;
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// `1 0^n 1^n`, accepted after reading all of it
+fn input(size: usize) -> Vec<Input> {
+  let mut input = vec![ONE];
+  input.extend((0..size / 2).map(|_| ZERO));
+  input.extend((0..size / 2).map(|_| ONE));
+  input
+}
+
+/// The generated function per state and generated `match`, against interpreting the DFA from a
+///   dense table and from the hash maps of `Dfa`. The function per state recurses once per
+///   symbol, so it relies on the optimiser turning those tail calls into jumps.
+pub fn styles(c: &mut Criterion) {
+  let dfa = language();
+  let table = dfa.to_table();
+  let mut group = c.benchmark_group("styles");
+  for size in SIZES {
+    let input = input(size);
+    group.throughput(Throughput::Elements(input.len() as u64));
+    let variants: [(&str, Accepts); 5] = [
+      ("functions", &functions::accepts),
+      ("trampoline", &trampoline::accepts),
+      ("match", &table_match::accepts),
+      ("table", &|input| table.accepts(input)),
+      ("Dfa::accepts", &|input| dfa.accepts(input)),
+    ];
+    for (name, accepts) in variants {
+      group.bench_with_input(BenchmarkId::new(name, size), &input, |b, input| {
+        b.iter(|| assert!(accepts(input)))
+      });
+    }
+  }
+  group.finish();
+}
+
+/// `implicit_state.rs` and the generated trampoline on inputs that would overflow the stack
+///   without tail calls, against the loop over a `match` of the blog post and the loop of
+///   `Dfa::accepts` in `main.rs`
+pub fn long(c: &mut Criterion) {
+  let dfa = language();
+  let input = input(10_000_000);
+  let mut group = c.benchmark_group("long");
+  group.sample_size(10);
+  group.throughput(Throughput::Elements(input.len() as u64));
+  let variants: [(&str, Accepts); 4] = [
+    ("implicit_state", &implicit_state::run),
+    ("trampoline", &trampoline::accepts),
+    ("match", &table_match::accepts),
+    ("Dfa::accepts", &|input| dfa.accepts(input)),
+  ];
+  for (name, accepts) in variants {
+    group.bench_with_input(BenchmarkId::new(name, input.len()), &input, |b, input| {
+      b.iter(|| assert!(accepts(input)))
+    });
+  }
+  group.finish();
+}
+
+criterion_group!(benches, styles, long);
+criterion_main!(benches);
@@ -0,0 +1,186 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use dfa::Dfa;
+use nfa::Nfa;
+use nfa_epsilon::NfaEpsilon;
+
+/// The states of the union of two automata through ε-transitions: a new start state, and the
+///   states of both automata kept apart
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Union<S, T> {
+  Start,
+  Left(S),
+  Right(T),
+}
+
+impl<S: Clone + Ord + Hash, A: Clone + Eq + Hash> NfaEpsilon<S, A> {
+  /// The union of the second finite automata post: a new start state with ε-transitions to the
+  ///   start states of both NFAs, so neither can reach back into its start state through the other
+  pub fn union<T>(left: &Nfa<S, A>, right: &Nfa<T, A>) -> NfaEpsilon<Union<S, T>, A>
+    where T: Clone + Ord + Hash {
+    let mut transitions = vec![
+      (Union::Start, None, Union::Left(left.start().clone())),
+      (Union::Start, None, Union::Right(right.start().clone())),
+    ];
+    for (from, symbol, to) in left.transitions() {
+      let (from, to) = (Union::Left(from.clone()), Union::Left(to.clone()));
+      transitions.push((from, Some(symbol.clone()), to));
+    }
+    for (from, symbol, to) in right.transitions() {
+      let (from, to) = (Union::Right(from.clone()), Union::Right(to.clone()));
+      transitions.push((from, Some(symbol.clone()), to));
+    }
+    let finals = left
+      .finals()
+      .map(|state| Union::Left(state.clone()))
+      .chain(right.finals().map(|state| Union::Right(state.clone())));
+    NfaEpsilon::new(Union::Start, finals, transitions)
+  }
+}
+
+/// The DFA of a product construction, with `None` for a side that is stuck
+pub type Product<S, T, A> = Dfa<(Option<S>, Option<T>), A>;
+
+impl<S: Clone + Ord + Hash, A: Clone + Ord + Hash> Dfa<S, A> {
+  /// The product construction, which runs both DFAs side by side. A side that gets stuck is
+  ///   `None` and stays that way, while the other side goes on if `follow_stuck` is set. The
+  ///   product gets stuck when both sides are, and accepts when `accept` says so about whether each
+  ///   side accepts.
+  pub fn product<T, F>(&self, other: &Dfa<T, A>, follow_stuck: bool, accept: F) -> Product<S, T, A>
+    where T: Clone + Ord + Hash,
+          F: Fn(bool, bool) -> bool {
+    let mut alphabet = self.alphabet();
+    alphabet.extend(other.alphabet());
+    alphabet.sort();
+    alphabet.dedup();
+    let start = (Some(self.start().clone()), Some(other.start().clone()));
+    let mut seen = HashSet::new();
+    seen.insert(start.clone());
+    let mut worklist = VecDeque::new();
+    worklist.push_back(start.clone());
+    let mut finals = vec![];
+    let mut transitions = vec![];
+    while let Some((left, right)) = worklist.pop_front() {
+      let left_accepts = left.as_ref().is_some_and(|state| self.is_final(state));
+      let right_accepts = right.as_ref().is_some_and(|state| other.is_final(state));
+      if accept(left_accepts, right_accepts) {
+        finals.push((left.clone(), right.clone()));
+      }
+      for symbol in &alphabet {
+        let next = (
+          left.as_ref().and_then(|state| self.transition(state, symbol)).cloned(),
+          right.as_ref().and_then(|state| other.transition(state, symbol)).cloned(),
+        );
+        let stuck = match next {
+          (None, None) => true,
+          (Some(_), Some(_)) => false,
+          _ => !follow_stuck,
+        };
+        if stuck {
+          continue;
+        }
+        if seen.insert(next.clone()) {
+          worklist.push_back(next.clone());
+        }
+        transitions.push(((left.clone(), right.clone()), symbol.clone(), next));
+      }
+    }
+    Dfa::new(start, finals, transitions)
+  }
+
+  /// The union through the product construction, which stays deterministic
+  pub fn union<T: Clone + Ord + Hash>(&self, other: &Dfa<T, A>) -> Product<S, T, A> {
+    self.product(other, true, |left, right| left || right)
+  }
+
+  /// The intersection through the product construction. Only pairs where neither side is stuck
+  ///   can still accept, so the others are left out.
+  pub fn intersection<T: Clone + Ord + Hash>(&self, other: &Dfa<T, A>) -> Product<S, T, A> {
+    self.product(other, false, |left, right| left && right)
+  }
+
+  /// The words of this DFA that the other one does not accept. Where the other DFA gets stuck
+  ///   it does not accept anything anymore, so only this side has to keep going.
+  pub fn difference<T: Clone + Ord + Hash>(&self, other: &Dfa<T, A>) -> Product<S, T, A> {
+    self.product(other, true, |left, right| left && !right)
+  }
+
+  /// The fully defined DFA over `alphabet`: every missing transition goes to the stuck state
+  ///   `None`, which has a transition to itself for every symbol
+  pub fn complete(&self, alphabet: &[A]) -> Dfa<Option<S>, A> {
+    let mut states: Vec<Option<S>> = self.states().into_iter().cloned().map(Some).collect();
+    states.push(None);
+    let mut transitions = vec![];
+    for state in &states {
+      for symbol in alphabet {
+        let next = state.as_ref().and_then(|state| self.transition(state, symbol)).cloned();
+        transitions.push((state.clone(), symbol.clone(), next));
+      }
+    }
+    let finals = self.finals().cloned().map(Some);
+    Dfa::new(Some(self.start().clone()), finals, transitions)
+  }
+
+  /// The words over `alphabet` that this DFA does not accept. The stuck state has to be made
+  ///   explicit first, because it is final in the complement.
+  pub fn complement(&self, alphabet: &[A]) -> Dfa<Option<S>, A> {
+    let complete = self.complete(alphabet);
+    let finals: Vec<Option<S>> = complete
+      .states()
+      .into_iter()
+      .filter(|state| !complete.is_final(state))
+      .cloned()
+      .collect();
+    let transitions: Vec<_> = complete
+      .transitions()
+      .map(|(from, symbol, to)| (from.clone(), symbol.clone(), to.clone()))
+      .collect();
+    Dfa::new(complete.start().clone(), finals, transitions)
+  }
+
+  /// One of the shortest words that the DFA accepts, found breadth-first
+  pub fn shortest_word(&self) -> Option<Vec<A>> {
+    let alphabet = self.alphabet();
+    let mut previous: HashMap<&S, Option<(&S, &A)>> = HashMap::new();
+    previous.insert(self.start(), None);
+    let mut worklist = VecDeque::new();
+    worklist.push_back(self.start());
+    while let Some(state) = worklist.pop_front() {
+      if self.is_final(state) {
+        let mut word = vec![];
+        let mut current = state;
+        while let Some((from, symbol)) = previous[current] {
+          word.push(symbol.clone());
+          current = from;
+        }
+        word.reverse();
+        return Some(word);
+      }
+      for symbol in &alphabet {
+        if let Some(next) = self.transition(state, symbol) {
+          if !previous.contains_key(next) {
+            previous.insert(next, Some((state, symbol)));
+            worklist.push_back(next);
+          }
+        }
+      }
+    }
+    None
+  }
+
+  /// Whether the DFA accepts no word at all, so no final state can be reached from the start
+  pub fn is_empty(&self) -> bool {
+    self.shortest_word().is_none()
+  }
+
+  /// Whether every word of this DFA is accepted by the other one
+  pub fn is_subset<T: Clone + Ord + Hash>(&self, other: &Dfa<T, A>) -> bool {
+    self.difference(other).is_empty()
+  }
+
+  /// Whether both DFAs accept the same words
+  pub fn is_equivalent<T: Clone + Ord + Hash>(&self, other: &Dfa<T, A>) -> bool {
+    self.product(other, true, |left, right| left != right).is_empty()
+  }
+}
@@ -0,0 +1,233 @@
+use std::hash::Hash;
+
+use dfa::Dfa;
+
+/// The two ways of compiling a DFA to Rust of the blog post
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Style {
+  /// A function per state that matches on the next input and calls the function of the next
+  ///   state. Rust does not promise that those tail calls become jumps, without that optimisation
+  ///   every symbol takes stack space.
+  Functions,
+  /// A function per state like [`Style::Functions`] that returns the function of the next state
+  ///   instead of calling it, and a loop that calls them, like `implicit_state.rs`
+  Trampoline,
+  /// A loop over the inputs with one `match` on the state and the input, like the `transition`
+  ///   function of the blog post
+  Match,
+}
+
+/// Symbols that can be written as patterns in generated Rust
+pub trait RustSymbol: Sized {
+  /// The type of the symbols in the generated code
+  fn type_name() -> &'static str;
+
+  /// A pattern that matches the symbol
+  fn pattern(&self) -> String;
+
+  /// Every value of the type, when there are few enough to match on them exhaustively
+  fn values() -> Option<Vec<Self>>;
+}
+
+impl RustSymbol for bool {
+  fn type_name() -> &'static str {
+    "bool"
+  }
+
+  fn pattern(&self) -> String {
+    self.to_string()
+  }
+
+  fn values() -> Option<Vec<bool>> {
+    Some(vec![false, true])
+  }
+}
+
+impl RustSymbol for char {
+  fn type_name() -> &'static str {
+    "char"
+  }
+
+  fn pattern(&self) -> String {
+    format!("{:?}", self)
+  }
+
+  fn values() -> Option<Vec<char>> {
+    None
+  }
+}
+
+/// How generated code writes the symbols, for when they have names of their own like the `ZERO`
+///   and `ONE` of the crate
+pub struct Names<A> {
+  /// The type of the symbols
+  pub type_name: &'static str,
+  /// A pattern that matches the symbol
+  pub pattern: fn(&A) -> String,
+}
+
+impl<A: RustSymbol> Names<A> {
+  /// The names that [`RustSymbol`] gives
+  pub fn rust() -> Self {
+    Names {
+      type_name: A::type_name(),
+      pattern: A::pattern,
+    }
+  }
+}
+
+impl<S: Clone + Eq + Hash, A: Clone + Ord + Hash + RustSymbol> Dfa<S, A> {
+  /// Rust source with a function `pub fn name(inputs: &[A]) -> bool` that says whether the DFA
+  ///   accepts the inputs, with the symbols written as `names` says. The states reachable from
+  ///   the start are numbered like [`Dfa::renumber`], in the [`Style::Functions`] and
+  ///   [`Style::Trampoline`] styles the function of state `n` is `name` and then `n`.
+  pub fn to_rust(&self, style: Style, name: &str, names: &Names<A>) -> String {
+    let dfa = self.renumber();
+    let states = dfa.states().len();
+    let mut source = format!(
+      "// Generated by binary_string from a DFA with {} states, in the {:?} style\n\n",
+      states, style
+    );
+    match style {
+      Style::Functions => {
+        source.push_str(&signature(names, "pub ", name, "inputs", "bool"));
+        source.push_str(&format!("  {}0(inputs)\n}}\n", name));
+      }
+      Style::Trampoline => source.push_str(&trampoline(names, name)),
+      Style::Match => {
+        source.push_str(&dfa.match_function(name, names));
+        return source;
+      }
+    }
+    let trampoline = style == Style::Trampoline;
+    for state in 0..states {
+      source.push('\n');
+      source.push_str(&dfa.state_function(state, name, names, trampoline));
+    }
+    source
+  }
+}
+
+impl<A: Clone + Ord + Hash + RustSymbol> Dfa<usize, A> {
+  /// The function of a state, which returns a `Bounce` on the `trampoline`
+  fn state_function(&self, state: usize, name: &str, names: &Names<A>, trampoline: bool)
+    -> String {
+    let output = if trampoline { "Bounce<'_>" } else { "bool" };
+    let next = |state: String| if trampoline {
+      format!("Bounce::Next({}, inputs)", state)
+    } else {
+      format!("{}(inputs)", state)
+    };
+    let done = |accept: String| if trampoline {
+      format!("Bounce::Done({})", accept)
+    } else {
+      accept
+    };
+    let accept = self.is_final(&state);
+    let alphabet = self.alphabet();
+    let mut arms: Vec<(String, String)> = vec![];
+    let mut stuck = vec![];
+    for symbol in &alphabet {
+      match self.transition(&state, symbol) {
+        Some(to) => arms.push(((names.pattern)(symbol), next(format!("{}{}", name, to)))),
+        None => stuck.push(symbol.clone()),
+      }
+    }
+    let function = format!("{}{}", name, state);
+    if arms.is_empty() {
+      let (inputs, body) = if accept {
+        ("inputs", done("inputs.is_empty()".to_string()))
+      } else {
+        ("_inputs", done("false".to_string()))
+      };
+      let signature = signature(names, "", &function, inputs, output);
+      return format!("{}  {}\n}}\n", signature, body);
+    }
+    match A::values() {
+      Some(values) => {
+        for value in values {
+          if alphabet.binary_search(&value).is_err() {
+            stuck.push(value);
+          }
+        }
+        let pattern = names.pattern;
+        arms.extend(stuck.iter().map(|symbol| (pattern(symbol), done("false".to_string()))));
+      }
+      None => arms.push(("_".to_string(), done("false".to_string()))),
+    }
+    let mut source = signature(names, "", &function, "inputs", output);
+    source.push_str("  match inputs.split_first() {\n");
+    source.push_str(&format!("    None => {},\n", done(accept.to_string())));
+    source.push_str("    Some((&input, inputs)) => match input {\n");
+    source.push_str(&aligned(&arms, "      "));
+    source.push_str("    },\n  }\n}\n");
+    source
+  }
+
+  fn match_function(&self, name: &str, names: &Names<A>) -> String {
+    let mut finals: Vec<usize> = self.finals().cloned().collect();
+    finals.sort();
+    if finals.is_empty() {
+      let signature = signature(names, "pub ", name, "_inputs", "bool");
+      return format!("{}  false\n}}\n", signature);
+    }
+    let accepted = if finals.len() == 1 {
+      format!("state == {}", finals[0])
+    } else {
+      let finals: Vec<String> = finals.iter().map(|state| state.to_string()).collect();
+      format!("matches!(state, {})", finals.join(" | "))
+    };
+    let mut transitions: Vec<(usize, A, usize)> = self
+      .transitions()
+      .map(|(from, symbol, to)| (*from, symbol.clone(), *to))
+      .collect();
+    if transitions.is_empty() {
+      let signature = signature(names, "pub ", name, "inputs", "bool");
+      return format!("{}  inputs.is_empty()\n}}\n", signature);
+    }
+    transitions.sort();
+    let pattern = names.pattern;
+    let mut arms: Vec<(String, String)> = transitions
+      .iter()
+      .map(|(from, symbol, to)| (format!("({}, {})", from, pattern(symbol)), to.to_string()))
+      .collect();
+    arms.push(("_".to_string(), "return false".to_string()));
+    let mut source = signature(names, "pub ", name, "inputs", "bool");
+    source.push_str("  let mut state = 0;\n");
+    source.push_str("  for &input in inputs {\n");
+    source.push_str("    state = match (state, input) {\n");
+    source.push_str(&aligned(&arms, "      "));
+    source.push_str("    };\n  }\n");
+    source.push_str(&format!("  {}\n}}\n", accepted));
+    source
+  }
+}
+
+fn signature<A>(names: &Names<A>, visibility: &str, name: &str, inputs: &str, output: &str)
+    -> String {
+  format!("{}fn {}({}: &[{}]) -> {} {{\n", visibility, name, inputs, names.type_name, output)
+}
+
+/// The `Bounce` of the state functions, and the loop that runs them from state 0
+fn trampoline<A>(names: &Names<A>, name: &str) -> String {
+  let input = names.type_name;
+  let mut source = format!("pub enum Bounce<'a> {{\n  Next(State<'a>, &'a [{}]),\n", input);
+  source.push_str("  Done(bool),\n}\n\n");
+  source.push_str(&format!("pub type State<'a> = fn(&'a [{}]) -> Bounce<'a>;\n\n", input));
+  source.push_str(&signature(names, "pub ", name, "inputs", "bool"));
+  source.push_str(&format!("  let mut bounce = Bounce::Next({}0, inputs);\n", name));
+  source.push_str("  loop {\n    match bounce {\n");
+  source.push_str("      Bounce::Next(state, inputs) => bounce = state(inputs),\n");
+  source.push_str("      Bounce::Done(accepted) => return accepted,\n");
+  source.push_str("    }\n  }\n}\n");
+  source
+}
+
+/// Match arms with their `=>` lined up, like the hand-written ones
+fn aligned(arms: &[(String, String)], indent: &str) -> String {
+  let width = arms.iter().map(|(pattern, _)| pattern.len()).max().unwrap_or(0);
+  arms
+    .iter()
+    .map(|(pattern, body)| format!("{}{:width$} => {},\n", indent, pattern, body, width = width))
+    .collect()
+}
@@ -0,0 +1,154 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::slice;
+
+/// A partially defined DFA: a state without a transition for a symbol gets stuck, like the `None`
+///   of the `transition` function in the blog post
+#[derive(Clone, Debug)]
+pub struct Dfa<S, A> {
+  transitions: HashMap<S, HashMap<A, S>>,
+  start: S,
+  finals: HashSet<S>,
+}
+
+impl<S: Clone + Eq + Hash, A: Eq + Hash> Dfa<S, A> {
+  /// The transitions are `(from, symbol, to)` triples, at most one per `from` and `symbol`
+  pub fn new<F, T>(start: S, finals: F, transitions: T) -> Self
+    where F: IntoIterator<Item = S>,
+          T: IntoIterator<Item = (S, A, S)> {
+    let mut table: HashMap<S, HashMap<A, S>> = HashMap::new();
+    for (from, symbol, to) in transitions {
+      let previous = table.entry(from).or_default().insert(symbol, to);
+      assert!(previous.is_none(), "a DFA has one transition per state and symbol");
+    }
+    Dfa {
+      transitions: table,
+      start,
+      finals: finals.into_iter().collect(),
+    }
+  }
+
+  pub fn start(&self) -> &S {
+    &self.start
+  }
+
+  pub fn finals(&self) -> impl Iterator<Item = &S> {
+    self.finals.iter()
+  }
+
+  pub fn is_final(&self, state: &S) -> bool {
+    self.finals.contains(state)
+  }
+
+  /// The start state, the final states and every state with a transition from or to it
+  pub fn states(&self) -> HashSet<&S> {
+    let mut states = HashSet::new();
+    states.insert(&self.start);
+    states.extend(&self.finals);
+    for (from, _, to) in self.transitions() {
+      states.insert(from);
+      states.insert(to);
+    }
+    states
+  }
+
+  /// Every transition as `(from, symbol, to)`, in no particular order
+  pub fn transitions(&self) -> impl Iterator<Item = (&S, &A, &S)> {
+    self.transitions
+      .iter()
+      .flat_map(|(from, row)| row.iter().map(move |(symbol, to)| (from, symbol, to)))
+  }
+
+  /// `None` when the DFA gets stuck
+  pub fn transition(&self, state: &S, symbol: &A) -> Option<&S> {
+    self.transitions.get(state).and_then(|row| row.get(symbol))
+  }
+
+  pub fn accepts(&self, input: &[A]) -> bool {
+    let mut state = &self.start;
+    for symbol in input {
+      match self.transition(state, symbol) {
+        Some(next) => state = next,
+        None => return false,
+      }
+    }
+    self.is_final(state)
+  }
+
+  /// The state after every symbol of the input, `None` from the symbol where the DFA gets stuck
+  pub fn run<'a>(&'a self, input: &'a [A]) -> Run<'a, S, A> {
+    Run {
+      dfa: self,
+      state: Some(&self.start),
+      input: input.iter(),
+    }
+  }
+}
+
+impl<S: Clone + Eq + Hash, A: Clone + Ord + Hash> Dfa<S, A> {
+  /// The symbols that have a transition somewhere, in order
+  pub fn alphabet(&self) -> Vec<A> {
+    let mut alphabet: Vec<A> = self.transitions().map(|(_, symbol, _)| symbol.clone()).collect();
+    alphabet.sort();
+    alphabet.dedup();
+    alphabet
+  }
+
+  /// Numbers the states reachable from the start breadth-first, following the transitions in the
+  ///   order of their symbols. Two DFAs are isomorphic when they give the same result here.
+  pub fn renumber(&self) -> Dfa<usize, A> {
+    let alphabet = self.alphabet();
+    let mut numbers = HashMap::new();
+    numbers.insert(&self.start, 0);
+    let mut worklist = VecDeque::new();
+    worklist.push_back(&self.start);
+    let mut transitions = vec![];
+    while let Some(state) = worklist.pop_front() {
+      for symbol in &alphabet {
+        if let Some(next) = self.transition(state, symbol) {
+          let count = numbers.len();
+          let number = *numbers.entry(next).or_insert_with(|| {
+            worklist.push_back(next);
+            count
+          });
+          transitions.push((numbers[state], symbol.clone(), number));
+        }
+      }
+    }
+    let finals = self.finals.iter().filter_map(|state| numbers.get(state).cloned());
+    Dfa::new(0, finals, transitions)
+  }
+}
+
+/// The same states, transitions and final states, so [`Dfa::renumber`] first to compare the
+///   structure of DFAs with different states
+impl<S: Eq + Hash, A: Eq + Hash> PartialEq for Dfa<S, A> {
+  fn eq(&self, other: &Self) -> bool {
+    self.start == other.start
+      && self.finals == other.finals
+      && self.transitions == other.transitions
+  }
+}
+
+impl<S: Eq + Hash, A: Eq + Hash> Eq for Dfa<S, A> {}
+
+pub struct Run<'a, S: 'a, A: 'a> {
+  dfa: &'a Dfa<S, A>,
+  state: Option<&'a S>,
+  input: slice::Iter<'a, A>,
+}
+
+impl<'a, S: Clone + Eq + Hash, A: Eq + Hash> Iterator for Run<'a, S, A> {
+  type Item = Option<&'a S>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let symbol = self.input.next()?;
+    let dfa = self.dfa;
+    self.state = self.state.and_then(|state| dfa.transition(state, symbol));
+    Some(self.state)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.input.size_hint()
+  }
+}
@@ -0,0 +1,39 @@
+extern crate binary_string;
+
+use std::env;
+use std::process;
+
+use binary_string::codegen::Style;
+use binary_string::{binary, language, Regex, INPUT_NAMES};
+
+// Prints the Rust source of the DFA of a regex over `0` and `1`, or of `binary_string::language`
+//   without one: `generate <functions|trampoline|match> [regex]`
+
+fn main() {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let style = match args.first().map(String::as_str) {
+    Some("functions") => Style::Functions,
+    Some("trampoline") => Style::Trampoline,
+    Some("match") => Style::Match,
+    _ => usage(),
+  };
+  let dfa = match args.get(1) {
+    None => language().renumber(),
+    Some(regex) => match Regex::parse(regex, binary) {
+      Ok(regex) => regex.to_dfa(),
+      Err(error) => {
+        eprintln!("{}", error);
+        process::exit(1);
+      }
+    },
+  };
+  if args.len() > 2 {
+    usage();
+  }
+  print!("{}", dfa.to_rust(style, "accepts", &INPUT_NAMES));
+}
+
+fn usage() -> ! {
+  eprintln!("usage: generate <functions|trampoline|match> [regex]");
+  process::exit(2);
+}
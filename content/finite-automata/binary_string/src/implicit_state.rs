@@ -1,66 +1,86 @@
-enum Input {
-  ZERO,
-  ONE,
-}
+extern crate binary_string;
+
+use binary_string::{language, Input, INPUTS, ONE, ZERO};
+
+// The DFA of `binary_string::language` compiled by hand, with a function per state. Rust does not
+//   promise to turn a call to the next state into a jump, so a state does not call the next state
+//   but returns it, and `run` calls one state after the other in a loop: a trampoline. The stack
+//   stays the same size however long the input is.
 
-fn transition0(inputs: &[Input]) {
-  inputs
-    .split_first()
-    .map_or_else(
-      || println!("The input is not accepted"),
-      |(input, inputs)| match input {
-        &Input::ONE  => transition1(inputs),
-        &Input::ZERO => println!("The input is not accepted"),
-      });
+pub enum Bounce<'a> {
+  /// Go on in the state with the rest of the inputs
+  Next(State<'a>, &'a [Input]),
+  Done(bool),
 }
 
-fn transition1(inputs: &[Input]) {
-  inputs
-    .split_first()
-    .map_or_else(
-      || println!("The input is not accepted"),
-      |(input, inputs)| match input {
-        &Input::ZERO => transition2(inputs),
-        &Input::ONE => println!("The input is not accepted"),
-      });
+pub type State<'a> = fn(&'a [Input]) -> Bounce<'a>;
+
+pub fn run(inputs: &[Input]) -> bool {
+  let mut bounce = Bounce::Next(transition0, inputs);
+  loop {
+    match bounce {
+      Bounce::Next(state, inputs) => bounce = state(inputs),
+      Bounce::Done(accepted) => return accepted,
+    }
+  }
 }
 
-fn transition2(inputs: &[Input]) {
-  inputs
-    .split_first()
-    .map_or_else(
-      || println!("The input is not accepted"),
-      |(input, inputs)| match input {
-        &Input::ZERO => transition3(inputs),
-        &Input::ONE => println!("The input is not accepted"),
-      });
+fn transition0(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(false),
+    Some((&input, inputs)) => match input {
+      ONE  => Bounce::Next(transition1, inputs),
+      ZERO => Bounce::Done(false),
+    },
+  }
 }
 
-fn transition3(inputs: &[Input]) {
-  inputs
-    .split_first()
-    .map_or_else(
-      || println!("The input is not accepted"),
-      |(input, inputs)| match input {
-        &Input::ZERO => transition3(inputs),
-        &Input::ONE  => transition4(inputs),
-      });
+fn transition1(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(false),
+    Some((&input, inputs)) => match input {
+      ZERO => Bounce::Next(transition2, inputs),
+      ONE  => Bounce::Done(false),
+    },
+  }
 }
 
-fn transition4(inputs: &[Input]) {
-  inputs
-    .split_first()
-    .map_or_else(
-      || println!("The input is accepted"), 
-      |(input, inputs)| match input {
-        &Input::ONE => transition4(inputs),
-        &Input::ZERO => println!("The input is not accepted"),
-      });
+fn transition2(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(false),
+    Some((&input, inputs)) => match input {
+      ZERO => Bounce::Next(transition3, inputs),
+      ONE  => Bounce::Done(false),
+    },
+  }
 }
 
+fn transition3(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(false),
+    Some((&input, inputs)) => match input {
+      ZERO => Bounce::Next(transition3, inputs),
+      ONE  => Bounce::Next(transition4, inputs),
+    },
+  }
+}
 
-const INPUTS: [Input; 7] = [Input::ONE, Input::ZERO, Input::ZERO, Input::ZERO, Input::ZERO, Input::ONE, Input::ONE];
+fn transition4(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(true),
+    Some((&input, inputs)) => match input {
+      ONE  => Bounce::Next(transition4, inputs),
+      ZERO => Bounce::Done(false),
+    },
+  }
+}
 
 fn main() {
-  transition0(&INPUTS);
+  let accepted = run(&INPUTS);
+  assert_eq!(accepted, language().accepts(&INPUTS), "the functions should implement the DFA");
+  if accepted {
+    println!("The input is accepted");
+  } else {
+    println!("The input is not accepted");
+  }
 }
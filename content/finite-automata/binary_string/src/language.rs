@@ -0,0 +1,20 @@
+use dfa::Dfa;
+
+pub type State = u8;
+
+pub type Input = bool;
+pub const ZERO: Input = false;
+pub const ONE: Input  = true;
+
+pub fn language() -> Dfa<State, Input> {
+  Dfa::new(0, vec![4], vec![
+    (0, ONE,  1),
+    (1, ZERO, 2),
+    (2, ZERO, 3),
+    (3, ZERO, 3),
+    (3, ONE,  4),
+    (4, ONE,  4),
+  ])
+}
+
+pub const INPUTS: [Input; 7] = [ONE, ZERO, ZERO, ZERO, ZERO, ONE, ONE];
@@ -0,0 +1,52 @@
+//! The binary string language of the blog post, `1 0 0+ 1+`: a one, at least two zeroes and then
+//!   at least one more one. All binaries share this definition.
+
+pub mod boolean;
+pub mod codegen;
+pub mod dfa;
+mod language;
+mod minimise;
+pub mod nfa;
+pub mod nfa_epsilon;
+pub mod observer;
+pub mod regex;
+pub mod table;
+pub mod words;
+
+use codegen::Names;
+
+pub use dfa::Dfa;
+pub use language::{language, Input, State, INPUTS, ONE, ZERO};
+pub use nfa::Nfa;
+pub use nfa_epsilon::NfaEpsilon;
+pub use observer::Observer;
+pub use regex::Regex;
+pub use table::Table;
+
+/// `q` and the number of the state, like in the pictures
+pub fn state_name(state: &State) -> String {
+  format!("q{}", state)
+}
+
+pub fn input_name(input: &Input) -> String {
+  if *input { "1".to_string() } else { "0".to_string() }
+}
+
+/// `Input` and its `ZERO` and `ONE` for [`Dfa::to_rust`], for generated code that uses this crate
+pub const INPUT_NAMES: Names<Input> = Names {
+  type_name: "Input",
+  pattern: input_constant,
+};
+
+fn input_constant(input: &Input) -> String {
+  if *input { "ONE".to_string() } else { "ZERO".to_string() }
+}
+
+/// The symbols of a [`Regex`] over binary strings: `0` and `1`
+pub fn binary(c: char) -> Option<Input> {
+  match c {
+    '0' => Some(ZERO),
+    '1' => Some(ONE),
+    _ => None,
+  }
+}
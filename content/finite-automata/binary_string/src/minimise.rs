@@ -0,0 +1,143 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+
+use dfa::Dfa;
+
+impl<S: Clone + Ord + Hash, A: Clone + Ord + Hash> Dfa<S, A> {
+  /// The powerset construction of the reversed DFA: the transitions turned around, the final
+  ///   states as the start set, and the sets with the old start state as the final states. Only
+  ///   the sets that are reachable from the start are built, the empty set is left out.
+  pub fn determinised_reverse(&self) -> Dfa<BTreeSet<S>, A> {
+    let alphabet = self.alphabet();
+    let mut predecessors: HashMap<(&S, &A), Vec<&S>> = HashMap::new();
+    for (from, symbol, to) in self.transitions() {
+      predecessors.entry((to, symbol)).or_default().push(from);
+    }
+    let start: BTreeSet<S> = self.finals().cloned().collect();
+    let mut seen = HashSet::new();
+    seen.insert(start.clone());
+    let mut worklist = vec![start.clone()];
+    let mut finals = vec![];
+    let mut transitions = vec![];
+    while let Some(states) = worklist.pop() {
+      if states.contains(self.start()) {
+        finals.push(states.clone());
+      }
+      for symbol in &alphabet {
+        let next: BTreeSet<S> = states
+          .iter()
+          .flat_map(|state| predecessors.get(&(state, symbol)).into_iter().flatten())
+          .map(|&state| state.clone())
+          .collect();
+        if next.is_empty() {
+          continue;
+        }
+        if seen.insert(next.clone()) {
+          worklist.push(next.clone());
+        }
+        transitions.push((states.clone(), symbol.clone(), next));
+      }
+    }
+    Dfa::new(start, finals, transitions)
+  }
+
+  /// Brzozowski's minimisation: determinising the reverse of a DFA gives a minimal DFA for the
+  ///   reversed language, so doing that twice gives a minimal DFA for the language. Only reachable
+  ///   sets are built and the empty set is left out, so the result has no unreachable or dead
+  ///   states.
+  pub fn minimise_brzozowski(&self) -> Dfa<usize, A> {
+    self.determinised_reverse().determinised_reverse().renumber()
+  }
+
+  /// Hopcroft's partition refinement on the states reachable from the start. The partial DFA is
+  ///   completed with a stuck state first, the block of states that behave like it (that cannot
+  ///   reach a final state) is left out of the result again.
+  pub fn minimise_hopcroft(&self) -> Dfa<usize, A> {
+    let reachable = self.renumber();
+    let alphabet = reachable.alphabet();
+    let states = reachable.states().len();
+    let stuck = states;
+    // predecessors[symbol][to], with the stuck state and its transitions to itself
+    let mut predecessors = vec![vec![vec![]; states + 1]; alphabet.len()];
+    for (a, symbol) in alphabet.iter().enumerate() {
+      for from in 0..states + 1 {
+        let to = reachable.transition(&from, symbol).cloned().unwrap_or(stuck);
+        predecessors[a][to].push(from);
+      }
+    }
+
+    let (finals, others): (Vec<usize>, Vec<usize>) =
+      (0..states + 1).partition(|state| reachable.is_final(state));
+    let mut blocks: Vec<Vec<usize>> = vec![];
+    let mut block_of = vec![0; states + 1];
+    for block in [finals, others] {
+      if !block.is_empty() {
+        for &state in &block {
+          block_of[state] = blocks.len();
+        }
+        blocks.push(block);
+      }
+    }
+    // splitters as (block, symbol), starting from the smaller of the two blocks
+    let mut worklist: Vec<(usize, usize)> = vec![];
+    let mut in_worklist = HashSet::new();
+    let smallest = (0..blocks.len()).min_by_key(|&block| blocks[block].len()).unwrap_or(0);
+    for a in 0..alphabet.len() {
+      worklist.push((smallest, a));
+      in_worklist.insert((smallest, a));
+    }
+
+    while let Some((splitter, a)) = worklist.pop() {
+      in_worklist.remove(&(splitter, a));
+      // the states with an `a` transition into the splitter, per block
+      let mut hits: HashMap<usize, Vec<usize>> = HashMap::new();
+      for &to in &blocks[splitter] {
+        for &from in &predecessors[a][to] {
+          hits.entry(block_of[from]).or_default().push(from);
+        }
+      }
+      for (block, mut inside) in hits {
+        inside.sort();
+        inside.dedup();
+        if inside.len() == blocks[block].len() {
+          continue;
+        }
+        let outside: Vec<usize> = blocks[block]
+          .iter()
+          .cloned()
+          .filter(|state| inside.binary_search(state).is_err())
+          .collect();
+        let new = blocks.len();
+        for &state in &outside {
+          block_of[state] = new;
+        }
+        blocks[block] = inside;
+        blocks.push(outside);
+        for b in 0..alphabet.len() {
+          let smaller = if blocks[new].len() <= blocks[block].len() { new } else { block };
+          let added = if in_worklist.contains(&(block, b)) { new } else { smaller };
+          if in_worklist.insert((added, b)) {
+            worklist.push((added, b));
+          }
+        }
+      }
+    }
+
+    let dead = block_of[stuck];
+    let mut transitions = vec![];
+    for (block, states) in blocks.iter().enumerate() {
+      if block == dead {
+        continue;
+      }
+      for symbol in &alphabet {
+        if let Some(to) = reachable.transition(&states[0], symbol) {
+          if block_of[*to] != dead {
+            transitions.push((block, symbol.clone(), block_of[*to]));
+          }
+        }
+      }
+    }
+    let finals: BTreeSet<usize> = reachable.finals().map(|state| block_of[*state]).collect();
+    Dfa::new(block_of[0], finals, transitions).renumber()
+  }
+}
@@ -0,0 +1,162 @@
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::slice;
+
+use dfa::Dfa;
+
+/// An NFA: a state can have any number of transitions for a symbol, so a run is in a set of states
+///   at once. It gets stuck when that set is empty.
+#[derive(Clone, Debug)]
+pub struct Nfa<S, A> {
+  transitions: HashMap<S, HashMap<A, BTreeSet<S>>>,
+  start: S,
+  finals: HashSet<S>,
+}
+
+impl<S: Clone + Ord + Hash, A: Clone + Eq + Hash> Nfa<S, A> {
+  /// The transitions are `(from, symbol, to)` triples
+  pub fn new<F, T>(start: S, finals: F, transitions: T) -> Self
+    where F: IntoIterator<Item = S>,
+          T: IntoIterator<Item = (S, A, S)> {
+    let mut table: HashMap<S, HashMap<A, BTreeSet<S>>> = HashMap::new();
+    for (from, symbol, to) in transitions {
+      table.entry(from).or_default().entry(symbol).or_default().insert(to);
+    }
+    Nfa {
+      transitions: table,
+      start,
+      finals: finals.into_iter().collect(),
+    }
+  }
+
+  pub fn start(&self) -> &S {
+    &self.start
+  }
+
+  pub fn finals(&self) -> impl Iterator<Item = &S> {
+    self.finals.iter()
+  }
+
+  pub fn is_final(&self, state: &S) -> bool {
+    self.finals.contains(state)
+  }
+
+  /// Every transition as `(from, symbol, to)`, in no particular order
+  pub fn transitions(&self) -> impl Iterator<Item = (&S, &A, &S)> {
+    self.transitions.iter().flat_map(|(from, row)| {
+      row.iter().flat_map(move |(symbol, targets)| targets.iter().map(move |to| (from, symbol, to)))
+    })
+  }
+
+  /// The states reachable from `state` with `symbol`
+  pub fn transition(&self, state: &S, symbol: &A) -> BTreeSet<S> {
+    self.transitions
+      .get(state)
+      .and_then(|row| row.get(symbol))
+      .cloned()
+      .unwrap_or_default()
+  }
+
+  /// The states reachable from any of `states` with `symbol`
+  pub fn step(&self, states: &BTreeSet<S>, symbol: &A) -> BTreeSet<S> {
+    let mut next = BTreeSet::new();
+    for state in states {
+      if let Some(targets) = self.transitions.get(state).and_then(|row| row.get(symbol)) {
+        next.extend(targets.iter().cloned());
+      }
+    }
+    next
+  }
+
+  pub fn accepts(&self, input: &[A]) -> bool {
+    let mut states = BTreeSet::new();
+    states.insert(self.start.clone());
+    for symbol in input {
+      states = self.step(&states, symbol);
+      if states.is_empty() {
+        return false;
+      }
+    }
+    states.iter().any(|state| self.is_final(state))
+  }
+
+  /// The set of states after every symbol of the input
+  pub fn run<'a>(&'a self, input: &'a [A]) -> Run<'a, S, A> {
+    let mut states = BTreeSet::new();
+    states.insert(self.start.clone());
+    Run {
+      nfa: self,
+      states,
+      input: input.iter(),
+    }
+  }
+
+  /// The powerset construction: every DFA state is the set of NFA states that a run can be in. Only
+  ///   the sets that are reachable from the start are built, with a worklist. The empty set is left
+  ///   out, it is where the DFA gets stuck.
+  pub fn to_dfa(&self) -> Dfa<BTreeSet<S>, A> {
+    let mut alphabet: Vec<&A> = vec![];
+    for row in self.transitions.values() {
+      for symbol in row.keys() {
+        if !alphabet.contains(&symbol) {
+          alphabet.push(symbol);
+        }
+      }
+    }
+    let mut start = BTreeSet::new();
+    start.insert(self.start.clone());
+    let mut seen = HashSet::new();
+    seen.insert(start.clone());
+    let mut worklist = VecDeque::new();
+    worklist.push_back(start.clone());
+    let mut finals = vec![];
+    let mut transitions = vec![];
+    while let Some(states) = worklist.pop_front() {
+      if states.iter().any(|state| self.is_final(state)) {
+        finals.push(states.clone());
+      }
+      for &symbol in &alphabet {
+        let next = self.step(&states, symbol);
+        if next.is_empty() {
+          continue;
+        }
+        if seen.insert(next.clone()) {
+          worklist.push_back(next.clone());
+        }
+        transitions.push((states.clone(), symbol.clone(), next));
+      }
+    }
+    Dfa::new(start, finals, transitions)
+  }
+}
+
+/// A DFA is an NFA with at most one transition per state and symbol
+impl<S: Clone + Ord + Hash, A: Clone + Eq + Hash> From<Dfa<S, A>> for Nfa<S, A> {
+  fn from(dfa: Dfa<S, A>) -> Self {
+    Nfa::new(
+      dfa.start().clone(),
+      dfa.finals().cloned(),
+      dfa.transitions().map(|(from, symbol, to)| (from.clone(), symbol.clone(), to.clone())),
+    )
+  }
+}
+
+pub struct Run<'a, S: 'a, A: 'a> {
+  nfa: &'a Nfa<S, A>,
+  states: BTreeSet<S>,
+  input: slice::Iter<'a, A>,
+}
+
+impl<'a, S: Clone + Ord + Hash, A: Clone + Eq + Hash> Iterator for Run<'a, S, A> {
+  type Item = BTreeSet<S>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let symbol = self.input.next()?;
+    self.states = self.nfa.step(&self.states, symbol);
+    Some(self.states.clone())
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.input.size_hint()
+  }
+}
@@ -0,0 +1,271 @@
+use std::cmp;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+
+use dfa::Dfa;
+use nfa::Nfa;
+
+/// An NFA with ε-transitions, which a run can take without reading a symbol. Like the `NFAE` of
+///   the second finite automata post, a transition on `None` is an ε-transition.
+#[derive(Clone, Debug)]
+pub struct NfaEpsilon<S, A> {
+  transitions: HashMap<S, HashMap<A, BTreeSet<S>>>,
+  epsilons: HashMap<S, BTreeSet<S>>,
+  start: S,
+  finals: HashSet<S>,
+}
+
+impl<S: Clone + Ord + Hash, A: Clone + Eq + Hash> NfaEpsilon<S, A> {
+  /// The transitions are `(from, symbol, to)` triples, with `None` for ε
+  pub fn new<F, T>(start: S, finals: F, transitions: T) -> Self
+    where F: IntoIterator<Item = S>,
+          T: IntoIterator<Item = (S, Option<A>, S)> {
+    let mut table: HashMap<S, HashMap<A, BTreeSet<S>>> = HashMap::new();
+    let mut epsilons: HashMap<S, BTreeSet<S>> = HashMap::new();
+    for (from, symbol, to) in transitions {
+      match symbol {
+        Some(symbol) => {
+          table.entry(from).or_default().entry(symbol).or_default().insert(to);
+        }
+        None => {
+          epsilons.entry(from).or_default().insert(to);
+        }
+      }
+    }
+    NfaEpsilon {
+      transitions: table,
+      epsilons,
+      start,
+      finals: finals.into_iter().collect(),
+    }
+  }
+
+  pub fn start(&self) -> &S {
+    &self.start
+  }
+
+  pub fn is_final(&self, state: &S) -> bool {
+    self.finals.contains(state)
+  }
+
+  /// Every state that shows up in the automaton
+  pub fn states(&self) -> BTreeSet<S> {
+    let mut states = BTreeSet::new();
+    states.insert(self.start.clone());
+    states.extend(self.finals.iter().cloned());
+    for (from, row) in &self.transitions {
+      states.insert(from.clone());
+      for targets in row.values() {
+        states.extend(targets.iter().cloned());
+      }
+    }
+    for (from, targets) in &self.epsilons {
+      states.insert(from.clone());
+      states.extend(targets.iter().cloned());
+    }
+    states
+  }
+
+  /// The ε-closures of all states. The strongly connected components of the ε-transitions come
+  ///   out of Tarjan's algorithm in reverse topological order, so the components an ε-transition
+  ///   leads to are numbered when a component is.
+  pub fn epsilon_closures(&self) -> EpsilonClosures<S> {
+    let mut closures = EpsilonClosures {
+      component: HashMap::new(),
+      components: vec![],
+      successors: vec![],
+      finals: vec![],
+    };
+    for states in self.sccs() {
+      let number = closures.components.len();
+      for state in &states {
+        closures.component.insert(state.clone(), number);
+      }
+      let successors: BTreeSet<usize> = states
+        .iter()
+        .flat_map(|state| self.epsilons.get(state).into_iter().flatten())
+        .map(|target| closures.component[target])
+        .filter(|&component| component != number)
+        .collect();
+      let finals = states.iter().any(|state| self.is_final(state))
+        || successors.iter().any(|&component| closures.finals[component]);
+      closures.components.push(states);
+      closures.successors.push(successors);
+      closures.finals.push(finals);
+    }
+    closures
+  }
+
+  /// Tarjan's strongly connected components of the ε-transitions, in reverse topological order
+  fn sccs(&self) -> Vec<Vec<S>> {
+    let mut tarjan = Tarjan {
+      epsilons: &self.epsilons,
+      index: 0,
+      indices: HashMap::new(),
+      lowlinks: HashMap::new(),
+      stack: vec![],
+      on_stack: HashSet::new(),
+      sccs: vec![],
+    };
+    for state in self.states() {
+      if !tarjan.indices.contains_key(&state) {
+        tarjan.strongconnect(state);
+      }
+    }
+    tarjan.sccs
+  }
+
+  /// ε-elimination: the start state and every state that a symbol leads to gets the transitions
+  ///   of the states in its ε-closure, and is final when its closure has a final state. The other
+  ///   states can only be reached through ε-transitions, so they are left out.
+  pub fn to_nfa(&self) -> Nfa<S, A> {
+    let closures = self.epsilon_closures();
+    let mut finals = vec![];
+    let mut transitions = vec![];
+    let mut seen = HashSet::new();
+    seen.insert(&self.start);
+    let mut worklist = vec![&self.start];
+    while let Some(state) = worklist.pop() {
+      if closures.is_final(state) {
+        finals.push(state.clone());
+      }
+      for reachable in closures.closure(Some(state)) {
+        for (symbol, targets) in self.transitions.get(&reachable).into_iter().flatten() {
+          for target in targets {
+            transitions.push((state.clone(), symbol.clone(), target.clone()));
+            if seen.insert(target) {
+              worklist.push(target);
+            }
+          }
+        }
+      }
+    }
+    Nfa::new(self.start.clone(), finals, transitions)
+  }
+
+  /// Through the NFA of [`NfaEpsilon::to_nfa`] and its powerset construction
+  pub fn to_dfa(&self) -> Dfa<BTreeSet<S>, A> {
+    self.to_nfa().to_dfa()
+  }
+
+  /// Simulates the automaton on the ε-closed set of states that it is in
+  pub fn accepts(&self, input: &[A]) -> bool {
+    let closures = self.epsilon_closures();
+    let mut states = closures.closure(Some(&self.start));
+    for symbol in input {
+      let targets = states
+        .iter()
+        .filter_map(|state| self.transitions.get(state).and_then(|row| row.get(symbol)))
+        .flatten();
+      let next = closures.closure(targets);
+      if next.is_empty() {
+        return false;
+      }
+      states = next;
+    }
+    states.iter().any(|state| self.is_final(state))
+  }
+}
+
+/// The ε-closures of the states of an [`NfaEpsilon`]. All states of a strongly connected component
+///   of the ε-transitions have the same closure: the states of the component and the closures of
+///   the components that its ε-transitions lead to. So a closure is kept once per component, as
+///   the numbers of those components, which keeps a long chain of ε-transitions linear in size
+///   where a set of states per state would be quadratic.
+#[derive(Clone, Debug)]
+pub struct EpsilonClosures<S> {
+  /// The number of the component of every state
+  component: HashMap<S, usize>,
+  /// The states of every component, in reverse topological order
+  components: Vec<Vec<S>>,
+  /// The other components that the ε-transitions of a component lead to
+  successors: Vec<BTreeSet<usize>>,
+  /// Whether the closure of a component has a final state
+  finals: Vec<bool>,
+}
+
+impl<S: Clone + Ord + Hash> EpsilonClosures<S> {
+  /// The states reachable from `states` with only ε-transitions, including `states` themselves
+  pub fn closure<'a, I>(&self, states: I) -> BTreeSet<S>
+    where I: IntoIterator<Item = &'a S>,
+          S: 'a {
+    let mut seen = vec![false; self.components.len()];
+    let mut worklist: Vec<usize> = states.into_iter().map(|state| self.component[state]).collect();
+    let mut closure = BTreeSet::new();
+    while let Some(component) = worklist.pop() {
+      if !seen[component] {
+        seen[component] = true;
+        closure.extend(self.components[component].iter().cloned());
+        worklist.extend(self.successors[component].iter().cloned());
+      }
+    }
+    closure
+  }
+
+  /// Whether the closure of `state` has a final state
+  pub fn is_final(&self, state: &S) -> bool {
+    self.finals[self.component[state]]
+  }
+}
+
+struct Tarjan<'a, S: 'a> {
+  epsilons: &'a HashMap<S, BTreeSet<S>>,
+  index: usize,
+  indices: HashMap<S, usize>,
+  lowlinks: HashMap<S, usize>,
+  stack: Vec<S>,
+  on_stack: HashSet<S>,
+  sccs: Vec<Vec<S>>,
+}
+
+impl<'a, S: Clone + Eq + Hash> Tarjan<'a, S> {
+  /// Without recursion: the work stack has a state and its ε-successors that are left to visit
+  ///   for every call that the recursive version would have on its call stack
+  fn strongconnect(&mut self, root: S) {
+    let epsilons = self.epsilons;
+    let successors = |state: &S| epsilons.get(state).into_iter().flatten();
+    self.visit(root.clone());
+    let mut work = vec![(root.clone(), successors(&root))];
+    while let Some((from, next)) = work.last_mut().map(|(from, rest)| (from.clone(), rest.next())) {
+      match next {
+        Some(to) if !self.indices.contains_key(to) => {
+          self.visit(to.clone());
+          work.push((to.clone(), successors(to)));
+        }
+        Some(to) => {
+          if self.on_stack.contains(to) {
+            let lowlink = cmp::min(self.lowlinks[&from], self.indices[to]);
+            self.lowlinks.insert(from, lowlink);
+          }
+        }
+        None => {
+          work.pop();
+          if let Some((parent, _)) = work.last() {
+            let lowlink = cmp::min(self.lowlinks[parent], self.lowlinks[&from]);
+            self.lowlinks.insert(parent.clone(), lowlink);
+          }
+          if self.lowlinks[&from] == self.indices[&from] {
+            let mut scc = vec![];
+            while let Some(state) = self.stack.pop() {
+              self.on_stack.remove(&state);
+              let done = state == from;
+              scc.push(state);
+              if done {
+                break;
+              }
+            }
+            self.sccs.push(scc);
+          }
+        }
+      }
+    }
+  }
+
+  fn visit(&mut self, state: S) {
+    self.indices.insert(state.clone(), self.index);
+    self.lowlinks.insert(state.clone(), self.index);
+    self.index += 1;
+    self.stack.push(state.clone());
+    self.on_stack.insert(state);
+  }
+}
@@ -0,0 +1,212 @@
+use std::hash::Hash;
+use std::io::{self, Write};
+
+use dfa::Dfa;
+
+/// Gets told about every step of a run of [`Dfa::accepts_observed`]. Every method does nothing by
+///   default.
+pub trait Observer<S, A> {
+  fn on_start(&mut self, _start: &S) {}
+
+  fn on_step(&mut self, _from: &S, _input: &A, _to: &S) {}
+
+  /// There is no transition from `from` on `input`, the rest of the input is not read
+  fn on_stuck(&mut self, _from: &S, _input: &A) {}
+
+  fn on_finish(&mut self, _accepted: bool) {}
+}
+
+impl<S: Clone + Eq + Hash, A: Eq + Hash> Dfa<S, A> {
+  /// Like [`Dfa::accepts`], but tells `observer` about the run
+  pub fn accepts_observed<O>(&self, input: &[A], observer: &mut O) -> bool
+    where O: Observer<S, A> + ?Sized {
+    let mut state = self.start();
+    observer.on_start(state);
+    for symbol in input {
+      match self.transition(state, symbol) {
+        Some(next) => {
+          observer.on_step(state, symbol, next);
+          state = next;
+        }
+        None => {
+          observer.on_stuck(state, symbol);
+          observer.on_finish(false);
+          return false;
+        }
+      }
+    }
+    let accepted = self.is_final(state);
+    observer.on_finish(accepted);
+    accepted
+  }
+}
+
+/// Writes the run like `observing.rs` in the blog post, with the names of states and inputs from
+///   the given functions. The first error of the writer stops the output and is kept for
+///   [`Text::into_inner`].
+pub struct Text<W, S, A> {
+  out: W,
+  state: fn(&S) -> String,
+  input: fn(&A) -> String,
+  error: Option<io::Error>,
+}
+
+impl<W: Write, S, A> Text<W, S, A> {
+  pub fn new(out: W, state: fn(&S) -> String, input: fn(&A) -> String) -> Self {
+    Text {
+      out,
+      state,
+      input,
+      error: None,
+    }
+  }
+
+  /// The writer, or the first error that writing to it gave
+  pub fn into_inner(self) -> io::Result<W> {
+    match self.error {
+      Some(error) => Err(error),
+      None => Ok(self.out),
+    }
+  }
+
+  fn line(&mut self, line: String) {
+    if self.error.is_none() {
+      self.error = writeln!(self.out, "{}", line).err();
+    }
+  }
+}
+
+impl<W: Write, S, A> Observer<S, A> for Text<W, S, A> {
+  fn on_start(&mut self, start: &S) {
+    let line = format!("The start state is: {}", (self.state)(start));
+    self.line(line);
+  }
+
+  fn on_step(&mut self, _from: &S, input: &A, to: &S) {
+    let line = format!("The input is: {}", (self.input)(input));
+    self.line(line);
+    let line = format!("The state is now: {}", (self.state)(to));
+    self.line(line);
+  }
+
+  fn on_stuck(&mut self, _from: &S, input: &A) {
+    let line = format!("The input is: {}", (self.input)(input));
+    self.line(line);
+    self.line("The state is now: STUCK".to_string());
+  }
+
+  fn on_finish(&mut self, accepted: bool) {
+    if accepted {
+      self.line("The input is accepted".to_string());
+    } else {
+      self.line("The input is not accepted".to_string());
+    }
+  }
+}
+
+/// Writes a JSON object per event on a line of its own, with the names of states and inputs from
+///   the given functions as strings:
+///
+/// ```text
+/// {"event":"start","state":"q0"}
+/// {"event":"step","from":"q0","input":"1","to":"q1"}
+/// {"event":"stuck","from":"q1","input":"1"}
+/// {"event":"finish","accepted":false}
+/// ```
+///
+/// Errors are kept like in [`Text`].
+pub struct JsonLines<W, S, A> {
+  text: Text<W, S, A>,
+}
+
+impl<W: Write, S, A> JsonLines<W, S, A> {
+  pub fn new(out: W, state: fn(&S) -> String, input: fn(&A) -> String) -> Self {
+    JsonLines {
+      text: Text::new(out, state, input),
+    }
+  }
+
+  pub fn into_inner(self) -> io::Result<W> {
+    self.text.into_inner()
+  }
+
+  fn state(&self, state: &S) -> String {
+    json_string(&(self.text.state)(state))
+  }
+
+  fn input(&self, input: &A) -> String {
+    json_string(&(self.text.input)(input))
+  }
+}
+
+impl<W: Write, S, A> Observer<S, A> for JsonLines<W, S, A> {
+  fn on_start(&mut self, start: &S) {
+    let line = format!(r#"{{"event":"start","state":{}}}"#, self.state(start));
+    self.text.line(line);
+  }
+
+  fn on_step(&mut self, from: &S, input: &A, to: &S) {
+    let line = format!(
+      r#"{{"event":"step","from":{},"input":{},"to":{}}}"#,
+      self.state(from),
+      self.input(input),
+      self.state(to)
+    );
+    self.text.line(line);
+  }
+
+  fn on_stuck(&mut self, from: &S, input: &A) {
+    let line =
+      format!(r#"{{"event":"stuck","from":{},"input":{}}}"#, self.state(from), self.input(input));
+    self.text.line(line);
+  }
+
+  fn on_finish(&mut self, accepted: bool) {
+    let line = format!(r#"{{"event":"finish","accepted":{}}}"#, accepted);
+    self.text.line(line);
+  }
+}
+
+/// A JSON string literal
+fn json_string(text: &str) -> String {
+  let mut json = String::from("\"");
+  for c in text.chars() {
+    match c {
+      '"' => json.push_str("\\\""),
+      '\\' => json.push_str("\\\\"),
+      '\n' => json.push_str("\\n"),
+      c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+      c => json.push(c),
+    }
+  }
+  json.push('"');
+  json
+}
+
+/// Counts the steps of the last run, and remembers how it ended
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Count {
+  /// The transitions taken
+  pub steps: usize,
+  pub stuck: bool,
+  /// `None` until the run finishes
+  pub accepted: Option<bool>,
+}
+
+impl<S, A> Observer<S, A> for Count {
+  fn on_start(&mut self, _start: &S) {
+    *self = Count::default();
+  }
+
+  fn on_step(&mut self, _from: &S, _input: &A, _to: &S) {
+    self.steps += 1;
+  }
+
+  fn on_stuck(&mut self, _from: &S, _input: &A) {
+    self.stuck = true;
+  }
+
+  fn on_finish(&mut self, accepted: bool) {
+    self.accepted = Some(accepted);
+  }
+}
@@ -0,0 +1,234 @@
+use std::hash::Hash;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use dfa::Dfa;
+use nfa_epsilon::NfaEpsilon;
+
+/// A regular expression over symbols of type `A`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Regex<A> {
+  /// The empty word, from `()` or an empty alternative
+  Empty,
+  Symbol(A),
+  /// One of the symbols, from a character class like `[01]`
+  Class(Vec<A>),
+  Concat(Vec<Regex<A>>),
+  Alternation(Vec<Regex<A>>),
+  Star(Box<Regex<A>>),
+  Plus(Box<Regex<A>>),
+  Optional(Box<Regex<A>>),
+}
+
+impl<A: Clone + Ord + Hash> Regex<A> {
+  /// Parses concatenation, `|`, `*`, `+`, `?`, grouping with `(` `)` and character classes like
+  ///   `[01]` or `[a-z]`. Whitespace is skipped, so `1 0 0+ 1+` is the language of the blog
+  ///   post, and `\` escapes the next character. Every other character is a symbol, which
+  ///   `symbol` turns into an `A`, or rejects with `None`.
+  pub fn parse<F>(text: &str, symbol: F) -> Result<Regex<A>, String>
+    where F: Fn(char) -> Option<A> {
+    let mut parser = Parser {
+      chars: text.char_indices().peekable(),
+      symbol,
+    };
+    let regex = parser.alternation()?;
+    match parser.next() {
+      None => Ok(regex),
+      Some((at, c)) => Err(format!("at {}: unexpected `{}`", at, c)),
+    }
+  }
+
+  /// Thompson's construction: every part of the expression becomes an automaton with one start
+  ///   and one final state, which are glued together with ε-transitions
+  pub fn to_nfa_epsilon(&self) -> NfaEpsilon<usize, A> {
+    let mut thompson = Thompson {
+      states: 0,
+      transitions: vec![],
+    };
+    let (start, end) = thompson.fragment(self);
+    NfaEpsilon::new(start, vec![end], thompson.transitions)
+  }
+
+  /// The minimal DFA, through [`Regex::to_nfa_epsilon`], the powerset construction and Hopcroft's
+  ///   minimisation
+  pub fn to_dfa(&self) -> Dfa<usize, A> {
+    self.to_nfa_epsilon().to_dfa().minimise_hopcroft()
+  }
+}
+
+struct Parser<'a, F> {
+  chars: Peekable<CharIndices<'a>>,
+  symbol: F,
+}
+
+impl<'a, A, F: Fn(char) -> Option<A>> Parser<'a, F> {
+  fn peek(&mut self) -> Option<char> {
+    while let Some(&(_, c)) = self.chars.peek() {
+      if !c.is_whitespace() {
+        return Some(c);
+      }
+      self.chars.next();
+    }
+    None
+  }
+
+  fn next(&mut self) -> Option<(usize, char)> {
+    self.peek()?;
+    self.chars.next()
+  }
+
+  fn symbol(&self, at: usize, c: char) -> Result<A, String> {
+    (self.symbol)(c).ok_or_else(|| format!("at {}: `{}` is not a symbol", at, c))
+  }
+
+  /// `concat ('|' concat)*`
+  fn alternation(&mut self) -> Result<Regex<A>, String> {
+    let mut alternatives = vec![self.concat()?];
+    while self.peek() == Some('|') {
+      self.next();
+      alternatives.push(self.concat()?);
+    }
+    if alternatives.len() == 1 {
+      Ok(alternatives.remove(0))
+    } else {
+      Ok(Regex::Alternation(alternatives))
+    }
+  }
+
+  /// `repeat*`, up to a `|`, a `)` or the end
+  fn concat(&mut self) -> Result<Regex<A>, String> {
+    let mut parts = vec![];
+    while let Some(c) = self.peek() {
+      if c == '|' || c == ')' {
+        break;
+      }
+      parts.push(self.repeat()?);
+    }
+    match parts.len() {
+      0 => Ok(Regex::Empty),
+      1 => Ok(parts.remove(0)),
+      _ => Ok(Regex::Concat(parts)),
+    }
+  }
+
+  /// `atom ('*' | '+' | '?')*`
+  fn repeat(&mut self) -> Result<Regex<A>, String> {
+    let mut regex = self.atom()?;
+    loop {
+      regex = match self.peek() {
+        Some('*') => Regex::Star(Box::new(regex)),
+        Some('+') => Regex::Plus(Box::new(regex)),
+        Some('?') => Regex::Optional(Box::new(regex)),
+        _ => return Ok(regex),
+      };
+      self.next();
+    }
+  }
+
+  fn atom(&mut self) -> Result<Regex<A>, String> {
+    match self.next() {
+      Some((_, '(')) => {
+        let regex = self.alternation()?;
+        match self.next() {
+          Some((_, ')')) => Ok(regex),
+          Some((at, c)) => Err(format!("at {}: expected `)` but found `{}`", at, c)),
+          None => Err("expected `)` but the expression ended".to_string()),
+        }
+      }
+      Some((_, '[')) => self.class(),
+      Some((_, '\\')) => match self.chars.next() {
+        Some((at, c)) => Ok(Regex::Symbol(self.symbol(at, c)?)),
+        None => Err("nothing to escape after `\\`".to_string()),
+      },
+      Some((at, c)) if "*+?])".contains(c) => Err(format!("at {}: unexpected `{}`", at, c)),
+      Some((at, c)) => Ok(Regex::Symbol(self.symbol(at, c)?)),
+      None => Err("the expression ended early".to_string()),
+    }
+  }
+
+  /// The symbols and ranges of a character class, after the `[`
+  fn class(&mut self) -> Result<Regex<A>, String> {
+    let mut symbols = vec![];
+    loop {
+      let (at, first) = match self.next() {
+        Some((_, ']')) => return Ok(Regex::Class(symbols)),
+        Some((_, '\\')) => self.chars.next().ok_or("nothing to escape after `\\`")?,
+        Some(next) => next,
+        None => return Err("expected `]` but the expression ended".to_string()),
+      };
+      if self.peek() != Some('-') {
+        symbols.push(self.symbol(at, first)?);
+        continue;
+      }
+      self.next();
+      let last = match self.next() {
+        Some((_, ']')) | None => return Err(format!("at {}: the range has no end", at)),
+        Some((_, last)) => last,
+      };
+      if last < first {
+        return Err(format!("at {}: the range `{}-{}` is empty", at, first, last));
+      }
+      for c in first..=last {
+        symbols.push(self.symbol(at, c)?);
+      }
+    }
+  }
+}
+
+struct Thompson<A> {
+  states: usize,
+  transitions: Vec<(usize, Option<A>, usize)>,
+}
+
+impl<A: Clone> Thompson<A> {
+  fn state(&mut self) -> usize {
+    self.states += 1;
+    self.states - 1
+  }
+
+  fn epsilon(&mut self, from: usize, to: usize) {
+    self.transitions.push((from, None, to));
+  }
+
+  /// The start and final state of a new automaton for `regex`
+  fn fragment(&mut self, regex: &Regex<A>) -> (usize, usize) {
+    let (start, end) = (self.state(), self.state());
+    match *regex {
+      Regex::Empty => self.epsilon(start, end),
+      Regex::Symbol(ref symbol) => self.transitions.push((start, Some(symbol.clone()), end)),
+      Regex::Class(ref symbols) => {
+        for symbol in symbols {
+          self.transitions.push((start, Some(symbol.clone()), end));
+        }
+      }
+      Regex::Concat(ref parts) => {
+        let mut last = start;
+        for part in parts {
+          let (part_start, part_end) = self.fragment(part);
+          self.epsilon(last, part_start);
+          last = part_end;
+        }
+        self.epsilon(last, end);
+      }
+      Regex::Alternation(ref alternatives) => {
+        for alternative in alternatives {
+          let (alternative_start, alternative_end) = self.fragment(alternative);
+          self.epsilon(start, alternative_start);
+          self.epsilon(alternative_end, end);
+        }
+      }
+      Regex::Star(ref inner) | Regex::Plus(ref inner) | Regex::Optional(ref inner) => {
+        let (inner_start, inner_end) = self.fragment(inner);
+        self.epsilon(start, inner_start);
+        self.epsilon(inner_end, end);
+        if let Regex::Star(_) | Regex::Optional(_) = *regex {
+          self.epsilon(start, end);
+        }
+        if let Regex::Star(_) | Regex::Plus(_) = *regex {
+          self.epsilon(inner_end, inner_start);
+        }
+      }
+    }
+    (start, end)
+  }
+}
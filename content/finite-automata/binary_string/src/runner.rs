@@ -0,0 +1,19 @@
+extern crate binary_string;
+
+use binary_string::observer::Text;
+use binary_string::{binary, input_name, language, state_name, words};
+
+// Runs `binary_string::language` on words of zeroes and ones, see `binary_string::words`. The
+//   trace is the output of `observing.rs`.
+
+fn main() {
+  let dfa = language();
+  words::main(binary, |input, trace| match trace {
+    Some(out) => {
+      let mut observer = Text::new(out, state_name, input_name);
+      let accepted = dfa.accepts_observed(input, &mut observer);
+      observer.into_inner().map(|_| accepted)
+    }
+    None => Ok(dfa.accepts(input)),
+  })
+}
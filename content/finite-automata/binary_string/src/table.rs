@@ -0,0 +1,49 @@
+use std::hash::Hash;
+
+use dfa::Dfa;
+
+/// A DFA as a dense transition table: a row per state and a column per symbol of the alphabet.
+///   Unlike [`Dfa::accepts`] a step is an index into a `Vec` rather than two hash lookups.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Table<A> {
+  alphabet: Vec<A>,
+  next: Vec<Option<usize>>,
+  finals: Vec<bool>,
+}
+
+impl<A: Ord> Table<A> {
+  /// The states are the row numbers of [`Dfa::renumber`], so the start state is row 0
+  pub fn accepts(&self, input: &[A]) -> bool {
+    let mut state = 0;
+    for symbol in input {
+      let column = match self.alphabet.binary_search(symbol) {
+        Ok(column) => column,
+        Err(_) => return false,
+      };
+      match self.next[state * self.alphabet.len() + column] {
+        Some(next) => state = next,
+        None => return false,
+      }
+    }
+    self.finals[state]
+  }
+}
+
+impl<S: Clone + Eq + Hash, A: Clone + Ord + Hash> Dfa<S, A> {
+  pub fn to_table(&self) -> Table<A> {
+    let dfa = self.renumber();
+    let alphabet = dfa.alphabet();
+    let states = dfa.states().len();
+    let mut next = Vec::with_capacity(states * alphabet.len());
+    let mut finals = Vec::with_capacity(states);
+    for state in 0..states {
+      next.extend(alphabet.iter().map(|symbol| dfa.transition(&state, symbol).cloned()));
+      finals.push(dfa.is_final(&state));
+    }
+    Table {
+      alphabet,
+      next,
+      finals,
+    }
+  }
+}
@@ -0,0 +1,96 @@
+//! The command line of the `runner` binaries, here and in `binary_palindrome` of the pushy
+//!   automata post. They run an automaton on words of zeroes and ones: the arguments, the lines of
+//!   a file with `--file`, or else the lines of stdin. Every word gets a line with the word, a tab
+//!   and `accept` or `reject`, after a trace of the run with `--trace`. The exit code is 0 when
+//!   every word is accepted, 1 when one is rejected, and 2 for bad arguments, words that are not
+//!   binary or input that cannot be read.
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process;
+
+const USAGE: &str = "usage: runner [--trace] [--file <path> | <word>...]";
+
+/// Reads the symbols of every word with `symbol` and runs `accepts` on them. With `--trace`,
+///   `accepts` gets the output to write the run to, and returns an error when that fails.
+pub fn main<A, F>(symbol: fn(char) -> Option<A>, accepts: F) -> !
+  where F: FnMut(&[A], Option<&mut dyn Write>) -> io::Result<bool> {
+  let mut trace = false;
+  let mut file = None;
+  let mut words = vec![];
+  let mut args = env::args().skip(1);
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--trace" => trace = true,
+      "--file" => file = Some(args.next().unwrap_or_else(|| usage())),
+      _ if arg.starts_with("--") => usage(),
+      _ => words.push(arg),
+    }
+  }
+  let stdin = io::stdin();
+  let lines: Box<dyn Iterator<Item = io::Result<String>>> = match file {
+    Some(_) if !words.is_empty() => usage(),
+    Some(path) => match File::open(&path) {
+      Ok(file) => Box::new(BufReader::new(file).lines()),
+      Err(error) => {
+        eprintln!("{}: {}", path, error);
+        process::exit(2);
+      }
+    },
+    None if words.is_empty() => Box::new(stdin.lock().lines()),
+    None => Box::new(words.into_iter().map(Ok)),
+  };
+  process::exit(run(lines, trace, symbol, accepts));
+}
+
+fn run<I, A, F>(lines: I, trace: bool, symbol: fn(char) -> Option<A>, mut accepts: F) -> i32
+  where I: Iterator<Item = io::Result<String>>,
+        F: FnMut(&[A], Option<&mut dyn Write>) -> io::Result<bool> {
+  let stdout = io::stdout();
+  let mut out = stdout.lock();
+  let mut code = 0;
+  for line in lines {
+    let mut word = match line {
+      Ok(word) => word,
+      Err(error) => {
+        eprintln!("{}", error);
+        return 2;
+      }
+    };
+    // `lines` leaves the `\r` of files with Windows line endings
+    if word.ends_with('\r') {
+      word.pop();
+    }
+    let input: Vec<A> = match word.chars().map(symbol).collect() {
+      Some(input) => input,
+      None => {
+        eprintln!("{:?} is not a string of zeroes and ones", word);
+        code = 2;
+        continue;
+      }
+    };
+    let accepted = if trace {
+      accepts(&input, Some(&mut out))
+    } else {
+      accepts(&input, None)
+    };
+    let accepted = match accepted {
+      Ok(accepted) => accepted,
+      Err(_) => return 2,
+    };
+    let verdict = if accepted { "accept" } else { "reject" };
+    if writeln!(out, "{}\t{}", word, verdict).is_err() {
+      return 2;
+    }
+    if !accepted && code == 0 {
+      code = 1;
+    }
+  }
+  code
+}
+
+fn usage() -> ! {
+  eprintln!("{}", USAGE);
+  process::exit(2);
+}
@@ -0,0 +1,119 @@
+extern crate binary_string;
+
+mod common;
+
+use binary_string::{language, Dfa, Input, Nfa, NfaEpsilon, ONE, ZERO};
+use common::strings;
+
+/// `binary-string-dfa-2.gv`: `0 1 0 0* 1 1*`
+fn dfa_2() -> Dfa<u8, Input> {
+  Dfa::new(5, vec![9], vec![
+    (5, ZERO, 6),
+    (6, ONE,  7),
+    (7, ZERO, 8),
+    (8, ZERO, 8),
+    (8, ONE,  9),
+    (9, ONE,  9),
+  ])
+}
+
+/// `binary-string-dfa-3.gv`: an odd number of zeroes and then a one
+fn dfa_3() -> Dfa<u8, Input> {
+  Dfa::new(0, vec![2], vec![
+    (0, ZERO, 1),
+    (1, ZERO, 0),
+    (1, ONE,  2),
+  ])
+}
+
+/// `binary-string-dfa-4.gv`: the single word `10`
+fn dfa_4() -> Dfa<u8, Input> {
+  Dfa::new(3, vec![5], vec![
+    (3, ONE,  4),
+    (4, ZERO, 5),
+  ])
+}
+
+/// Strings that end in a one
+fn ends_in_one() -> Dfa<u8, Input> {
+  Dfa::new(0, vec![1], vec![
+    (0, ZERO, 0),
+    (0, ONE,  1),
+    (1, ZERO, 0),
+    (1, ONE,  1),
+  ])
+}
+
+#[test]
+fn union_with_epsilons_and_product_agree() {
+  let pairs = vec![(language(), dfa_2()), (dfa_3(), dfa_4()), (dfa_3(), language())];
+  for (left, right) in pairs {
+    let nfa_e = NfaEpsilon::union(&Nfa::from(left.clone()), &Nfa::from(right.clone()));
+    let product = left.union(&right);
+    for string in strings(10) {
+      let expected = left.accepts(&string) || right.accepts(&string);
+      assert_eq!(nfa_e.accepts(&string), expected, "{:?}", string);
+      assert_eq!(product.accepts(&string), expected, "{:?}", string);
+    }
+    assert!(nfa_e.to_dfa().is_equivalent(&product));
+  }
+}
+
+#[test]
+fn the_union_of_3_and_4_does_not_accept_0010() {
+  // the word that the naive union of `wrong-nfa-union-3-4.gv` accepts
+  let union = dfa_3().union(&dfa_4());
+  assert!(!union.accepts(&[ZERO, ZERO, ONE, ZERO]));
+  assert!(union.accepts(&[ONE, ZERO]));
+  assert!(union.accepts(&[ZERO, ZERO, ZERO, ONE]));
+}
+
+#[test]
+fn intersection_and_difference() {
+  let intersection = language().intersection(&ends_in_one());
+  let difference = ends_in_one().difference(&language());
+  for string in strings(10) {
+    let (left, right) = (language().accepts(&string), ends_in_one().accepts(&string));
+    assert_eq!(intersection.accepts(&string), left && right, "{:?}", string);
+    assert_eq!(difference.accepts(&string), right && !left, "{:?}", string);
+  }
+}
+
+#[test]
+fn complement_accepts_the_stuck_words() {
+  let complement = language().complement(&[ZERO, ONE]);
+  for string in strings(10) {
+    assert_eq!(complement.accepts(&string), !language().accepts(&string), "{:?}", string);
+  }
+  // `0` gets stuck in `transition`, so it is in the complement
+  assert!(complement.accepts(&[ZERO]));
+  assert!(complement.accepts(&[]));
+}
+
+#[test]
+fn emptiness() {
+  assert!(!language().is_empty());
+  assert_eq!(language().shortest_word(), Some(vec![ONE, ZERO, ZERO, ONE]));
+  assert_eq!(dfa_4().shortest_word(), Some(vec![ONE, ZERO]));
+  assert!(language().intersection(&dfa_2()).is_empty());
+  assert!(dfa_3().intersection(&dfa_4()).is_empty());
+  assert!(language().difference(&language()).is_empty());
+}
+
+#[test]
+fn inclusion() {
+  assert!(language().is_subset(&ends_in_one()));
+  assert!(!ends_in_one().is_subset(&language()));
+  assert!(dfa_4().is_subset(&dfa_3().union(&dfa_4())));
+  assert!(!dfa_4().is_subset(&dfa_3()));
+}
+
+#[test]
+fn equivalence() {
+  assert!(language().is_equivalent(&language().minimise_hopcroft()));
+  assert!(language().is_equivalent(&Nfa::from(language()).to_dfa()));
+  assert!(!language().is_equivalent(&dfa_2()));
+  assert!(language().is_equivalent(&language().complement(&[ZERO, ONE]).complement(&[ZERO, ONE])));
+  let union = dfa_3().union(&dfa_4());
+  assert!(union.is_equivalent(&dfa_4().union(&dfa_3())));
+}
@@ -0,0 +1,161 @@
+extern crate binary_string;
+
+mod common;
+
+use std::thread;
+
+use binary_string::codegen::{Names, Style};
+use binary_string::{language, Regex, INPUT_NAMES, ONE, ZERO};
+use common::strings;
+
+mod functions {
+  use binary_string::{Input, ONE, ZERO};
+  include!("../benches/generated/functions.rs");
+}
+
+mod trampoline {
+  use binary_string::{Input, ONE, ZERO};
+  include!("../benches/generated/trampoline.rs");
+}
+
+mod table_match {
+  use binary_string::{Input, ONE, ZERO};
+  include!("../benches/generated/match.rs");
+}
+
+#[test]
+fn generated_sources_are_up_to_date() {
+  assert_eq!(
+    language().to_rust(Style::Functions, "accepts", &INPUT_NAMES),
+    include_str!("../benches/generated/functions.rs"),
+    "run `cargo run --bin generate -- functions > benches/generated/functions.rs`"
+  );
+  assert_eq!(
+    language().to_rust(Style::Trampoline, "accepts", &INPUT_NAMES),
+    include_str!("../benches/generated/trampoline.rs"),
+    "run `cargo run --bin generate -- trampoline > benches/generated/trampoline.rs`"
+  );
+  assert_eq!(
+    language().to_rust(Style::Match, "accepts", &INPUT_NAMES),
+    include_str!("../benches/generated/match.rs"),
+    "run `cargo run --bin generate -- match > benches/generated/match.rs`"
+  );
+}
+
+#[test]
+fn generated_functions_implement_the_dfa() {
+  let dfa = language();
+  let table = dfa.to_table();
+  for string in strings(12) {
+    let expected = dfa.accepts(&string);
+    assert_eq!(functions::accepts(&string), expected, "{:?}", string);
+    assert_eq!(trampoline::accepts(&string), expected, "{:?}", string);
+    assert_eq!(table_match::accepts(&string), expected, "{:?}", string);
+    assert_eq!(table.accepts(&string), expected, "{:?}", string);
+  }
+}
+
+#[test]
+fn the_functions_style_is_implicit_state() {
+  let source = language().to_rust(Style::Functions, "transition", &INPUT_NAMES);
+  assert!(source.contains(
+    "fn transition3(inputs: &[Input]) -> bool {
+  match inputs.split_first() {
+    None => false,
+    Some((&input, inputs)) => match input {
+      ZERO => transition3(inputs),
+      ONE  => transition4(inputs),
+    },
+  }
+}
+"
+  ));
+}
+
+#[test]
+fn the_trampoline_style_is_implicit_state() {
+  let source = language().to_rust(Style::Trampoline, "transition", &INPUT_NAMES);
+  assert!(source.contains(
+    "fn transition3(inputs: &[Input]) -> Bounce<'_> {
+  match inputs.split_first() {
+    None => Bounce::Done(false),
+    Some((&input, inputs)) => match input {
+      ZERO => Bounce::Next(transition3, inputs),
+      ONE  => Bounce::Next(transition4, inputs),
+    },
+  }
+}
+"
+  ));
+}
+
+#[test]
+fn the_trampoline_runs_long_inputs_on_a_small_stack() {
+  let result = thread::Builder::new()
+    .stack_size(64 * 1024)
+    .spawn(|| {
+      let mut input = vec![ONE];
+      input.extend((0..10_000_000).map(|i| if i < 5_000_000 { ZERO } else { ONE }));
+      trampoline::accepts(&input)
+    })
+    .unwrap()
+    .join()
+    .unwrap();
+  assert!(result);
+}
+
+#[test]
+fn booleans_without_names_of_their_own() {
+  let source = language().to_rust(Style::Match, "accepts", &Names::rust());
+  assert!(source.starts_with(
+    "// Generated by binary_string from a DFA with 5 states, in the Match style
+
+pub fn accepts(inputs: &[bool]) -> bool {
+  let mut state = 0;
+  for &input in inputs {
+    state = match (state, input) {
+      (0, true)  => 1,
+      (1, false) => 2,
+"
+  ));
+}
+
+#[test]
+fn symbols_without_exhaustive_values_get_a_wildcard() {
+  let dfa = Regex::parse("ab*", Some).unwrap().to_dfa();
+  let functions = dfa.to_rust(Style::Functions, "accepts", &Names::rust());
+  assert!(functions.contains(
+    "fn accepts1(inputs: &[char]) -> bool {
+  match inputs.split_first() {
+    None => true,
+    Some((&input, inputs)) => match input {
+      'b' => accepts1(inputs),
+      _   => false,
+    },
+  }
+}
+"
+  ));
+  assert!(dfa.to_rust(Style::Match, "accepts", &Names::rust()).ends_with(
+    "    state = match (state, input) {
+      (0, 'a') => 1,
+      (1, 'b') => 1,
+      _        => return false,
+    };
+  }
+  state == 1
+}
+"
+  ));
+}
+
+#[test]
+fn languages_without_transitions() {
+  let empty_word = Regex::parse("", Some).unwrap().to_dfa();
+  let names = Names::rust();
+  let functions = empty_word.to_rust(Style::Functions, "accepts", &names);
+  assert!(functions.contains("  inputs.is_empty()\n"));
+  assert!(empty_word.to_rust(Style::Match, "accepts", &names).contains("  inputs.is_empty()\n"));
+  assert!(empty_word.to_table().accepts(&[]));
+  assert!(!empty_word.to_table().accepts(&['a']));
+}
@@ -0,0 +1,20 @@
+use binary_string::{Input, ONE, ZERO};
+
+/// Every binary string up to `max_length` symbols
+pub fn strings(max_length: usize) -> Vec<Vec<Input>> {
+  let mut all = vec![vec![]];
+  let mut last = vec![vec![]];
+  for _ in 0..max_length {
+    let mut next = vec![];
+    for string in &last {
+      for &symbol in &[ZERO, ONE] {
+        let mut longer: Vec<Input> = string.clone();
+        longer.push(symbol);
+        next.push(longer);
+      }
+    }
+    all.extend(next.iter().cloned());
+    last = next;
+  }
+  all
+}
@@ -0,0 +1,45 @@
+extern crate binary_string;
+
+mod common;
+
+#[allow(dead_code)]
+#[path = "../src/implicit_state.rs"]
+mod implicit_state;
+
+use std::thread;
+
+use binary_string::{language, Input, ONE, ZERO};
+use common::strings;
+
+/// `1 0^n 1^n`
+fn long_input(size: usize) -> Vec<Input> {
+  let mut input = vec![ONE];
+  input.extend((0..size / 2).map(|_| ZERO));
+  input.extend((0..size / 2).map(|_| ONE));
+  input
+}
+
+#[test]
+fn implements_the_dfa() {
+  let dfa = language();
+  for string in strings(12) {
+    assert_eq!(implicit_state::run(&string), dfa.accepts(&string), "{:?}", string);
+  }
+}
+
+#[test]
+fn ten_million_symbols_on_a_small_stack() {
+  // a stack frame per symbol would need far more than 64 KiB
+  let result = thread::Builder::new()
+    .stack_size(64 * 1024)
+    .spawn(|| {
+      let mut input = long_input(10_000_000);
+      let accepted = implicit_state::run(&input);
+      input.push(ZERO);
+      (accepted, implicit_state::run(&input))
+    })
+    .unwrap()
+    .join()
+    .unwrap();
+  assert_eq!(result, (true, false));
+}
@@ -0,0 +1,116 @@
+extern crate binary_string;
+
+mod common;
+
+use binary_string::{language, Dfa, Input, Nfa, NfaEpsilon, ONE, ZERO};
+use common::strings;
+
+/// The `transition` function of the blog post, before it became `language()`
+fn transition(state: u8, symbol: Input) -> Option<u8> {
+  match (state, symbol) {
+    (0, ONE)  => Option::Some(1),
+    (1, ZERO) => Option::Some(2),
+    (2, ZERO) => Option::Some(3),
+    (3, ZERO) => Option::Some(3),
+    (3, ONE)  => Option::Some(4),
+    (4, ONE)  => Option::Some(4),
+    _ => Option::None,
+  }
+}
+
+fn transition_accepts(input: &[Input]) -> bool {
+  let mut state = Option::Some(0);
+  for &symbol in input {
+    state = state.and_then(|st| transition(st, symbol));
+  }
+  state == Some(4)
+}
+
+/// `language()` with every state doubled, and a dead end and an unreachable state on top
+fn redundant() -> Dfa<u8, Input> {
+  Dfa::new(0, vec![4, 14], vec![
+    (0,  ONE,  11),
+    (11, ZERO, 2),
+    (11, ONE,  20),
+    (2,  ZERO, 13),
+    (13, ZERO, 3),
+    (3,  ZERO, 13),
+    (13, ONE,  4),
+    (3,  ONE,  14),
+    (4,  ONE,  14),
+    (14, ONE,  4),
+    (20, ZERO, 20),
+    (30, ONE,  0),
+  ])
+}
+
+/// The powerset construction of `binary-string-nfa.gv`, which is minimal with its eight states
+fn third_to_last() -> Dfa<std::collections::BTreeSet<u8>, Input> {
+  Nfa::new(1, vec![4], vec![
+    (1, ZERO, 1),
+    (1, ONE,  1),
+    (1, ONE,  2),
+    (2, ZERO, 3),
+    (2, ONE,  3),
+    (3, ZERO, 4),
+    (3, ONE,  4),
+  ]).to_dfa()
+}
+
+#[test]
+fn both_algorithms_give_isomorphic_results() {
+  assert_eq!(language().minimise_hopcroft(), language().minimise_brzozowski());
+  assert_eq!(redundant().minimise_hopcroft(), redundant().minimise_brzozowski());
+  assert_eq!(third_to_last().minimise_hopcroft(), third_to_last().minimise_brzozowski());
+}
+
+#[test]
+fn minimal_dfas_accept_the_language_of_transition() {
+  let minimal = [
+    language().minimise_hopcroft(),
+    language().minimise_brzozowski(),
+    redundant().minimise_hopcroft(),
+    redundant().minimise_brzozowski(),
+  ];
+  for dfa in minimal.iter() {
+    for string in strings(10) {
+      assert_eq!(dfa.accepts(&string), transition_accepts(&string), "{:?}", string);
+    }
+  }
+}
+
+#[test]
+fn minimal_dfas_have_the_minimal_number_of_states() {
+  // the five states of `main.rs`, which is minimal by construction
+  assert_eq!(language().minimise_hopcroft().states().len(), 5);
+  assert_eq!(redundant().minimise_hopcroft().states().len(), 5);
+  assert_eq!(redundant().minimise_hopcroft(), language().renumber());
+  assert_eq!(third_to_last().minimise_hopcroft().states().len(), 8);
+}
+
+#[test]
+fn minimising_the_powerset_of_an_nfa_e() {
+  let nfa_e = NfaEpsilon::new(0, vec![3, 5], vec![
+    (0, None,       1),
+    (0, None,       4),
+    (1, Some(ONE),  2),
+    (2, Some(ZERO), 3),
+    (4, Some(ONE),  5),
+    (5, Some(ZERO), 3),
+  ]);
+  // `10` twice over, which the powerset construction does not merge
+  let dfa = nfa_e.to_dfa();
+  let minimal = dfa.minimise_hopcroft();
+  assert_eq!(minimal, dfa.minimise_brzozowski());
+  assert_eq!(minimal.states().len(), 3);
+  for string in strings(6) {
+    assert_eq!(minimal.accepts(&string), nfa_e.accepts(&string), "{:?}", string);
+  }
+}
+
+#[test]
+fn the_empty_language_is_one_state() {
+  let empty: Dfa<u8, Input> = Dfa::new(0, vec![], vec![(0, ONE, 1), (1, ZERO, 0)]);
+  assert_eq!(empty.minimise_hopcroft(), Dfa::new(0, vec![], vec![]));
+  assert_eq!(empty.minimise_brzozowski(), Dfa::new(0, vec![], vec![]));
+}
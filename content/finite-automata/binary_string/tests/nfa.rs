@@ -0,0 +1,87 @@
+extern crate binary_string;
+
+mod common;
+
+use binary_string::{language, Dfa, Input, Nfa, ONE, ZERO};
+use common::strings;
+
+/// `binary-string-nfa.gv`: the third to last symbol is a one, with `...` = 1, `..1` = 2, `.1.` = 3
+///   and `1..` = 4
+fn third_to_last_nfa() -> Nfa<u8, Input> {
+  Nfa::new(1, vec![4], vec![
+    (1, ZERO, 1),
+    (1, ONE,  1),
+    (1, ONE,  2),
+    (2, ZERO, 3),
+    (2, ONE,  3),
+    (3, ZERO, 4),
+    (3, ONE,  4),
+  ])
+}
+
+/// `binary-string-dfa-memory.gv`: the states remember the last three symbols, `q101` = 0b101
+fn third_to_last_dfa() -> Dfa<u8, Input> {
+  let mut transitions = vec![];
+  for state in 0..8u8 {
+    transitions.push((state, ZERO, (state << 1) & 0b111));
+    transitions.push((state, ONE,  ((state << 1) | 1) & 0b111));
+  }
+  Dfa::new(0, vec![0b100, 0b101, 0b110, 0b111], transitions)
+}
+
+#[test]
+fn nfa_accepts_the_third_to_last_one() {
+  let nfa = third_to_last_nfa();
+  for string in strings(10) {
+    let expected = string.len() >= 3 && string[string.len() - 3] == ONE;
+    assert_eq!(nfa.accepts(&string), expected, "{:?}", string);
+  }
+}
+
+#[test]
+fn nfa_and_memory_dfa_accept_the_same_strings() {
+  let nfa = third_to_last_nfa();
+  let dfa = third_to_last_dfa();
+  for string in strings(10) {
+    assert_eq!(nfa.accepts(&string), dfa.accepts(&string), "{:?}", string);
+  }
+}
+
+#[test]
+fn powerset_construction_accepts_the_same_strings() {
+  let nfa = third_to_last_nfa();
+  let dfa = nfa.to_dfa();
+  for string in strings(10) {
+    assert_eq!(nfa.accepts(&string), dfa.accepts(&string), "{:?}", string);
+  }
+}
+
+#[test]
+fn powerset_construction_builds_the_memory_dfa() {
+  // the eight states of `binary-string-dfa-memory.gv`, nothing more
+  let dfa = third_to_last_nfa().to_dfa();
+  let mut states: Vec<_> = dfa.transitions().map(|(from, _, _)| from.clone()).collect();
+  states.sort();
+  states.dedup();
+  assert_eq!(states.len(), 8);
+  assert_eq!(dfa.finals().count(), 4);
+}
+
+#[test]
+fn main_dfa_as_nfa_accepts_the_same_strings() {
+  let dfa = language();
+  let nfa = Nfa::from(language());
+  let powerset = nfa.to_dfa();
+  for string in strings(10) {
+    assert_eq!(nfa.accepts(&string), dfa.accepts(&string), "{:?}", string);
+    assert_eq!(powerset.accepts(&string), dfa.accepts(&string), "{:?}", string);
+  }
+}
+
+#[test]
+fn run_yields_the_sets_of_states() {
+  let nfa = third_to_last_nfa();
+  let input = [ONE, ZERO, ONE];
+  let states: Vec<Vec<u8>> = nfa.run(&input).map(|set| set.into_iter().collect()).collect();
+  assert_eq!(states, vec![vec![1, 2], vec![1, 3], vec![1, 2, 4]]);
+}
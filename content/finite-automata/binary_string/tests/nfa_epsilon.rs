@@ -0,0 +1,163 @@
+extern crate binary_string;
+
+mod common;
+
+use std::hash::Hash;
+use std::thread;
+
+use binary_string::nfa_epsilon::EpsilonClosures;
+use binary_string::{Input, Nfa, NfaEpsilon, ONE, ZERO};
+use common::strings;
+
+/// `binary-string-dfa-3.gv` and `binary-string-dfa-4.gv`: an odd number of zeroes and then a one,
+///   or the single word `10`
+fn in_union_3_4(string: &[Input]) -> bool {
+  let odd_zeroes_then_one = string.split_last().is_some_and(|(&last, rest)| {
+    last == ONE && rest.len() % 2 == 1 && rest.iter().all(|&s| s == ZERO)
+  });
+  odd_zeroes_then_one || string == [ONE, ZERO]
+}
+
+/// `nfa-e-union-3-4.gv`
+fn nfa_e_union_3_4() -> NfaEpsilon<&'static str, Input> {
+  NfaEpsilon::new("q00", vec!["q2", "q5"], vec![
+    ("q00", None,       "q0"),
+    ("q00", None,       "q3"),
+    ("q0",  Some(ZERO), "q1"),
+    ("q1",  Some(ZERO), "q0"),
+    ("q1",  Some(ONE),  "q2"),
+    ("q3",  Some(ONE),  "q4"),
+    ("q4",  Some(ZERO), "q5"),
+  ])
+}
+
+/// `nfa-union-3-4.gv`, the union without ε-transitions
+fn nfa_union_3_4() -> Nfa<&'static str, Input> {
+  Nfa::new("q00", vec!["q2", "q5"], vec![
+    ("q00", ZERO, "q1"),
+    ("q00", ONE,  "q4"),
+    ("q0",  ZERO, "q1"),
+    ("q1",  ZERO, "q0"),
+    ("q1",  ONE,  "q2"),
+    ("q3",  ONE,  "q4"),
+    ("q4",  ZERO, "q5"),
+  ])
+}
+
+/// `wrong-nfa-union-3-4.gv`, with the start states merged
+fn wrong_nfa_union_3_4() -> Nfa<&'static str, Input> {
+  Nfa::new("q0", vec!["q2", "q5"], vec![
+    ("q0", ZERO, "q1"),
+    ("q1", ZERO, "q0"),
+    ("q1", ONE,  "q2"),
+    ("q0", ONE,  "q4"),
+    ("q4", ZERO, "q5"),
+  ])
+}
+
+#[test]
+fn nfa_e_union_accepts_the_union() {
+  let nfa_e = nfa_e_union_3_4();
+  for string in strings(10) {
+    assert_eq!(nfa_e.accepts(&string), in_union_3_4(&string), "{:?}", string);
+  }
+}
+
+#[test]
+fn epsilon_elimination_accepts_the_same_strings() {
+  let nfa_e = nfa_e_union_3_4();
+  let nfa = nfa_e.to_nfa();
+  let dfa = nfa_e.to_dfa();
+  let direct = nfa_union_3_4();
+  for string in strings(10) {
+    let expected = in_union_3_4(&string);
+    assert_eq!(nfa.accepts(&string), expected, "{:?}", string);
+    assert_eq!(dfa.accepts(&string), expected, "{:?}", string);
+    assert_eq!(direct.accepts(&string), expected, "{:?}", string);
+  }
+}
+
+#[test]
+fn merging_the_start_states_is_wrong() {
+  let wrong = wrong_nfa_union_3_4();
+  assert!(wrong.accepts(&[ZERO, ZERO, ONE, ZERO]));
+  assert!(!nfa_e_union_3_4().accepts(&[ZERO, ZERO, ONE, ZERO]));
+}
+
+/// The ε-closure of `state`, in order
+fn closure<S: Clone + Ord + Hash>(closures: &EpsilonClosures<S>, state: &S) -> Vec<S> {
+  closures.closure(Some(state)).into_iter().collect()
+}
+
+#[test]
+fn epsilon_closure_of_the_new_start_state() {
+  let closures = nfa_e_union_3_4().epsilon_closures();
+  assert_eq!(closure(&closures, &"q00"), vec!["q0", "q00", "q3"]);
+  assert_eq!(closure(&closures, &"q0"), vec!["q0"]);
+}
+
+#[test]
+fn epsilon_cycles_share_their_closure() {
+  // 1 -ε-> 2 -ε-> 3 -ε-> 1 is one component, which reaches 4 and through it 5
+  let nfa_e = NfaEpsilon::new(0, vec![5], vec![
+    (0, Some(ONE),  1),
+    (1, None,       2),
+    (2, None,       3),
+    (3, None,       1),
+    (3, None,       4),
+    (4, None,       5),
+    (2, Some(ZERO), 0),
+  ]);
+  let closures = nfa_e.epsilon_closures();
+  let cycle: Vec<u8> = vec![1, 2, 3, 4, 5];
+  for state in 1..4 {
+    assert_eq!(closure(&closures, &state), cycle);
+  }
+  assert_eq!(closure(&closures, &4), vec![4, 5]);
+  assert_eq!(closure(&closures, &0), vec![0]);
+  // 5 is final, and every closure with it too
+  for state in 1..6 {
+    assert!(closures.is_final(&state), "{}", state);
+  }
+  assert!(!closures.is_final(&0));
+  let nfa = nfa_e.to_nfa();
+  for string in strings(8) {
+    assert_eq!(nfa.accepts(&string), nfa_e.accepts(&string), "{:?}", string);
+    let expected = !string.is_empty()
+      && string.chunks(2).all(|pair| pair == [ONE, ZERO] || pair == [ONE]);
+    assert_eq!(nfa_e.accepts(&string), expected && string.last() == Some(&ONE), "{:?}", string);
+  }
+}
+
+/// `0 -ε-> 1 -ε-> ... -ε-> length`, and then a one to the final state
+fn epsilon_chain(length: usize) -> NfaEpsilon<usize, Input> {
+  let mut transitions: Vec<(usize, Option<Input>, usize)> =
+    (0..length).map(|state| (state, None, state + 1)).collect();
+  transitions.push((length, Some(ONE), length + 1));
+  NfaEpsilon::new(0, vec![length + 1], transitions)
+}
+
+#[test]
+fn long_epsilon_chains() {
+  let nfa_e = epsilon_chain(100_000);
+  assert!(nfa_e.accepts(&[ONE]));
+  assert!(!nfa_e.accepts(&[]));
+  assert!(!nfa_e.accepts(&[ONE, ONE]));
+  let dfa = nfa_e.to_dfa();
+  assert!(dfa.accepts(&[ONE]));
+  assert!(!dfa.accepts(&[ZERO]));
+}
+
+#[test]
+fn epsilon_closures_of_a_long_chain_on_a_small_stack() {
+  // a recursive Tarjan needs a stack frame per state of the chain
+  let closures = thread::Builder::new()
+    .stack_size(64 * 1024)
+    .spawn(|| epsilon_chain(2_000).epsilon_closures())
+    .unwrap()
+    .join()
+    .unwrap();
+  assert_eq!(closures.closure(Some(&0)).len(), 2_001);
+  assert_eq!(closure(&closures, &1_999), vec![1_999, 2_000]);
+  assert_eq!(closures.closure(Some(&2_001)).len(), 1);
+}
@@ -0,0 +1,117 @@
+extern crate binary_string;
+
+use std::io::{self, Write};
+
+use binary_string::observer::{Count, JsonLines, Text};
+use binary_string::{input_name, language, state_name, Input, Observer, State, INPUTS, ONE, ZERO};
+
+/// Every call, to check the order
+#[derive(Default)]
+struct Events(Vec<String>);
+
+impl Observer<State, Input> for Events {
+  fn on_start(&mut self, start: &State) {
+    self.0.push(format!("start {}", start));
+  }
+
+  fn on_step(&mut self, from: &State, input: &Input, to: &State) {
+    self.0.push(format!("step {} {} {}", from, input_name(input), to));
+  }
+
+  fn on_stuck(&mut self, from: &State, input: &Input) {
+    self.0.push(format!("stuck {} {}", from, input_name(input)));
+  }
+
+  fn on_finish(&mut self, accepted: bool) {
+    self.0.push(format!("finish {}", accepted));
+  }
+}
+
+#[test]
+fn events_in_order() {
+  let mut events = Events::default();
+  assert!(!language().accepts_observed(&[ONE, ZERO, ONE, ONE], &mut events));
+  assert_eq!(events.0, vec!["start 0", "step 0 1 1", "step 1 0 2", "stuck 2 1", "finish false"]);
+  let mut events = Events::default();
+  assert!(!language().accepts_observed(&[ONE, ZERO], &mut events));
+  assert_eq!(events.0, vec!["start 0", "step 0 1 1", "step 1 0 2", "finish false"]);
+}
+
+#[test]
+fn text_like_observing() {
+  let mut text = Text::new(vec![], state_name, input_name);
+  assert!(!language().accepts_observed(&INPUTS[..3], &mut text));
+  let out = String::from_utf8(text.into_inner().unwrap()).unwrap();
+  assert_eq!(out, "The start state is: q0
+The input is: 1
+The state is now: q1
+The input is: 0
+The state is now: q2
+The input is: 0
+The state is now: q3
+The input is not accepted
+");
+  let mut text = Text::new(vec![], state_name, input_name);
+  language().accepts_observed(&[ZERO], &mut text);
+  let out = String::from_utf8(text.into_inner().unwrap()).unwrap();
+  assert_eq!(out, "The start state is: q0
+The input is: 0
+The state is now: STUCK
+The input is not accepted
+");
+}
+
+#[test]
+fn json_lines() {
+  let mut json = JsonLines::new(vec![], state_name, input_name);
+  language().accepts_observed(&[ONE, ONE], &mut json);
+  let out = String::from_utf8(json.into_inner().unwrap()).unwrap();
+  assert_eq!(out, r#"{"event":"start","state":"q0"}
+{"event":"step","from":"q0","input":"1","to":"q1"}
+{"event":"stuck","from":"q1","input":"1"}
+{"event":"finish","accepted":false}
+"#);
+  let mut json = JsonLines::new(vec![], |state: &State| format!("\"{}\"\n", state), input_name);
+  language().accepts_observed(&[], &mut json);
+  let out = String::from_utf8(json.into_inner().unwrap()).unwrap();
+  assert_eq!(out, r#"{"event":"start","state":"\"0\"\n"}
+{"event":"finish","accepted":false}
+"#);
+}
+
+#[test]
+fn count() {
+  let mut count = Count::default();
+  assert!(language().accepts_observed(&INPUTS, &mut count));
+  assert_eq!(count, Count { steps: INPUTS.len(), stuck: false, accepted: Some(true) });
+  language().accepts_observed(&[ONE, ONE, ONE], &mut count);
+  assert_eq!(count, Count { steps: 1, stuck: true, accepted: Some(false) });
+}
+
+#[test]
+fn trait_objects() {
+  let mut observers: Vec<Box<dyn Observer<State, Input>>> =
+    vec![Box::new(Count::default()), Box::new(Events::default())];
+  for observer in &mut observers {
+    assert!(language().accepts_observed(&INPUTS, &mut **observer));
+  }
+}
+
+struct Broken;
+
+impl Write for Broken {
+  fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+    Err(io::Error::other("broken"))
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+#[test]
+fn write_errors_are_kept() {
+  let mut text = Text::new(Broken, state_name, input_name);
+  assert!(language().accepts_observed(&INPUTS, &mut text));
+  assert_eq!(text.into_inner().err().map(|e| e.to_string()), Some("broken".to_string()));
+}
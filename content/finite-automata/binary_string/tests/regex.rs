@@ -0,0 +1,95 @@
+extern crate binary_string;
+
+mod common;
+
+use binary_string::{binary, language, Input, Regex, INPUTS, ONE, ZERO};
+use common::strings;
+
+type Predicate = fn(&[Input]) -> bool;
+
+fn parse(text: &str) -> Regex<Input> {
+  Regex::parse(text, binary).unwrap()
+}
+
+#[test]
+fn the_blog_post_language_accepts_the_inputs() {
+  let dfa = parse("100+1+").to_dfa();
+  assert!(dfa.accepts(&INPUTS));
+  assert!(parse("100+1+").to_nfa_epsilon().accepts(&INPUTS));
+  assert_eq!(dfa, language().minimise_hopcroft());
+  assert_eq!(parse("1 0 0+ 1+").to_dfa(), dfa);
+}
+
+#[test]
+fn parse_tree() {
+  let regex = parse("(0|1)*1?[01]");
+  assert_eq!(regex, Regex::Concat(vec![
+    Regex::Star(Box::new(Regex::Alternation(vec![Regex::Symbol(ZERO), Regex::Symbol(ONE)]))),
+    Regex::Optional(Box::new(Regex::Symbol(ONE))),
+    Regex::Class(vec![ZERO, ONE]),
+  ]));
+  assert_eq!(parse(""), Regex::Empty);
+  assert_eq!(parse("0|"), Regex::Alternation(vec![Regex::Symbol(ZERO), Regex::Empty]));
+}
+
+#[test]
+fn operators_agree_with_predicates() {
+  let cases: Vec<(&str, Predicate)> = vec![
+    ("", |s| s.is_empty()),
+    ("()*", |s| s.is_empty()),
+    ("0*", |s| s.iter().all(|&c| c == ZERO)),
+    ("0+", |s| !s.is_empty() && s.iter().all(|&c| c == ZERO)),
+    ("1?0", |s| s == [ZERO] || s == [ONE, ZERO]),
+    ("[01]*1[01][01]", |s| s.len() >= 3 && s[s.len() - 3] == ONE),
+    ("(00|1)*", |s| s.split(|&c| c == ONE).all(|zeroes| zeroes.len() % 2 == 0)),
+    ("(0|11)+|1", |s| {
+      s == [ONE] || !s.is_empty() && s.split(|&c| c == ZERO).all(|ones| ones.len() % 2 == 0)
+    }),
+  ];
+  for (text, predicate) in cases {
+    let regex = parse(text);
+    let nfa_e = regex.to_nfa_epsilon();
+    let dfa = regex.to_dfa();
+    for string in strings(8) {
+      assert_eq!(nfa_e.accepts(&string), predicate(&string), "{} on {:?}", text, string);
+      assert_eq!(dfa.accepts(&string), predicate(&string), "{} on {:?}", text, string);
+    }
+  }
+}
+
+#[test]
+fn the_dfa_is_minimal() {
+  let dfa = parse("(0|1)*(0|1)(0|1)|0(0|1)(0|1)*").to_dfa();
+  assert_eq!(dfa, dfa.minimise_brzozowski());
+  assert!(dfa.is_equivalent(&parse("[01][01][01]*").to_dfa()));
+}
+
+#[test]
+fn character_ranges_and_escapes() {
+  let letters = Regex::parse("[a-c]x\\+", Some).unwrap();
+  assert_eq!(letters, Regex::Concat(vec![
+    Regex::Class(vec!['a', 'b', 'c']),
+    Regex::Symbol('x'),
+    Regex::Symbol('+'),
+  ]));
+  let dfa = letters.to_dfa();
+  assert!(dfa.accepts(&['b', 'x', '+']));
+  assert!(!dfa.accepts(&['d', 'x', '+']));
+}
+
+#[test]
+fn errors() {
+  let error = |text| Regex::parse(text, binary).unwrap_err();
+  assert_eq!(error("102"), "at 2: `2` is not a symbol");
+  assert_eq!(error("(01"), "expected `)` but the expression ended");
+  assert_eq!(error("01)"), "at 2: unexpected `)`");
+  assert_eq!(error("*0"), "at 0: unexpected `*`");
+  assert_eq!(error("[1-0]"), "at 1: the range `1-0` is empty");
+  assert_eq!(error("[01"), "expected `]` but the expression ended");
+}
+
+#[test]
+fn long_chains_of_empty_groups() {
+  let dfa = parse(&("()".repeat(100_000) + "1")).to_dfa();
+  assert_eq!(dfa, parse("1").to_dfa());
+}
@@ -0,0 +1,79 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::{self, Command, Output, Stdio};
+
+fn runner(args: &[&str], stdin: &str) -> Output {
+  let mut child = Command::new(env!("CARGO_BIN_EXE_runner"))
+    .args(args)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap();
+  child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+  child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+  String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn words_from_arguments() {
+  let output = runner(&["1000011", "1001"], "");
+  assert_eq!(stdout(&output), "1000011\taccept\n1001\taccept\n");
+  assert_eq!(output.status.code(), Some(0));
+  let output = runner(&["1001", "101", ""], "");
+  assert_eq!(stdout(&output), "1001\taccept\n101\treject\n\treject\n");
+  assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn words_from_stdin() {
+  let output = runner(&[], "10011\n0\n");
+  assert_eq!(stdout(&output), "10011\taccept\n0\treject\n");
+  assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn words_from_a_file() {
+  let path = env::temp_dir().join(format!("binary_string_runner_{}.txt", process::id()));
+  fs::write(&path, "1001\n10001111\n").unwrap();
+  let output = runner(&["--file", path.to_str().unwrap()], "");
+  fs::remove_file(&path).unwrap();
+  assert_eq!(stdout(&output), "1001\taccept\n10001111\taccept\n");
+  assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn windows_line_endings() {
+  let output = runner(&[], "1001\r\n101\r\n");
+  assert_eq!(stdout(&output), "1001\taccept\n101\treject\n");
+  assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn trace_like_observing() {
+  let output = runner(&["--trace", "10"], "");
+  assert_eq!(stdout(&output), "The start state is: q0
+The input is: 1
+The state is now: q1
+The input is: 0
+The state is now: q2
+The input is not accepted
+10\treject
+");
+}
+
+#[test]
+fn errors() {
+  let output = runner(&["1021", "1001"], "");
+  assert_eq!(stdout(&output), "1001\taccept\n");
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert_eq!(stderr, "\"1021\" is not a string of zeroes and ones\n");
+  assert_eq!(output.status.code(), Some(2));
+  assert_eq!(runner(&["--verbose"], "").status.code(), Some(2));
+  assert_eq!(runner(&["--file"], "").status.code(), Some(2));
+  assert_eq!(runner(&["--file", "/does/not/exist"], "").status.code(), Some(2));
+}
@@ -0,0 +1,9 @@
+//! The PDA for even-length binary palindromes of the blog post, the version that always either
+//!   advances in the input or, at the end of the input, on the stack.
+
+mod pda;
+mod simulation;
+
+pub use pda::{epsilon_transition, transition, InputSymbol, Stack, StackSymbol, State};
+pub use pda::{EOS, FINALS, INPUT};
+pub use simulation::accepts;
@@ -0,0 +1,64 @@
+pub type State = u8;
+pub type InputSymbol = u8;
+pub type StackSymbol = u8;
+pub type Stack = Vec<StackSymbol>;
+
+pub const EOS: StackSymbol = 2; // End of Stack symbol
+
+pub fn transition(state: State, input: InputSymbol, stack: Stack)
+    -> Vec<(State, Stack)> {
+  match state {
+    0 => {
+      let mut new_stack = stack.clone();
+      new_stack.push(input);
+      vec![ (1,new_stack) ]
+    }
+    1 => {
+      let mut new_stack1 = stack.clone();
+      new_stack1.push(input);
+      match stack.last().map(|&top| top == input) {
+        Some(true) => {
+          let mut new_stack2 = stack.clone();
+          new_stack2.pop();
+          vec![ (1,new_stack1), (2,new_stack2) ]
+        }
+        Some(false) => {
+          vec![ (1,new_stack1) ]
+        }
+        None => Vec::new()
+      }
+    }
+    2 => {
+      if stack.last().map_or(false, |&top| top == input) {
+        let mut new_stack = stack.clone();
+        new_stack.pop();
+        vec![ (2,new_stack) ]
+      }
+      else {
+        Vec::new()
+      }
+    }
+    _ => Vec::new()
+  }
+}
+
+pub fn epsilon_transition(state: State, stack: Stack) -> Vec<(State, Stack)> {
+  match state {
+    2 => {
+      if stack.last().map_or(false, |&top| top == EOS) {
+        let mut new_stack = stack.clone();
+        new_stack.pop();
+        vec![ (3,new_stack) ]
+      }
+      else {
+        Vec::new()
+      }
+    }
+    _ => Vec::new()
+  }
+}
+
+pub const FINALS: [State; 2] = [0, 3];
+
+pub const INPUT: [InputSymbol; 16] =
+  [0, 0, 1, 0, 1, 0, 1, 1, 1, 1, 0, 1, 0, 1, 0, 0];
@@ -0,0 +1,32 @@
+extern crate binary_palindrome;
+extern crate binary_string;
+
+use binary_palindrome::{accepts, InputSymbol};
+use binary_string::words;
+
+// Runs the PDA on words of zeroes and ones, see `binary_string::words`. The trace is the
+//   configurations that `main.rs` prints.
+
+fn main() {
+  words::main(symbol, |input, trace| match trace {
+    Some(out) => {
+      let mut written = Ok(());
+      let accepted = accepts(input, |pda_states| {
+        if written.is_ok() {
+          written = writeln!(out, "{:?}", pda_states);
+        }
+      });
+      let verdict = if accepted { "accepted" } else { "not accepted" };
+      written.and_then(|()| writeln!(out, "The input is {}", verdict)).map(|()| accepted)
+    }
+    None => Ok(accepts(input, |_| {})),
+  })
+}
+
+fn symbol(c: char) -> Option<InputSymbol> {
+  match c {
+    '0' => Some(0),
+    '1' => Some(1),
+    _ => None,
+  }
+}
@@ -0,0 +1,30 @@
+use pda::{epsilon_transition, transition, InputSymbol, Stack, State, EOS, FINALS};
+
+/// Runs the PDA in every configuration it can be in at once, and shows `observe` those
+///   configurations before every step
+pub fn accepts<F>(input: &[InputSymbol], mut observe: F) -> bool
+    where F: FnMut(&[(State, Stack)]) {
+  let mut pda_states = vec![(0,vec![EOS])];
+  for &input in input.iter() {
+    observe(&pda_states);
+    let mut new_pda_states = vec![];
+    for (state, stack) in pda_states {
+      new_pda_states.append(&mut transition(state, input, stack));
+    }
+    pda_states = new_pda_states;
+  }
+  while !pda_states.is_empty() {
+    observe(&pda_states);
+    let mut new_pda_states = vec![];
+    for (state, stack) in pda_states {
+      if FINALS.iter().any(|&x| x == state) {
+        return true;
+      }
+      else {
+        new_pda_states.append(&mut epsilon_transition(state, stack));
+      }
+    }
+    pda_states = new_pda_states;
+  }
+  false
+}
@@ -0,0 +1,56 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn runner(args: &[&str]) -> Output {
+  Command::new(env!("CARGO_BIN_EXE_runner")).args(args).output().unwrap()
+}
+
+#[test]
+fn even_palindromes_are_accepted() {
+  let output = runner(&["0110", "", "0010101111010100"]);
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert_eq!(stdout, "0110\taccept\n\taccept\n0010101111010100\taccept\n");
+  assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn other_words_are_rejected() {
+  let output = runner(&["010", "01", "0110"]);
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert_eq!(stdout, "010\treject\n01\treject\n0110\taccept\n");
+  assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn windows_line_endings() {
+  let mut child = Command::new(env!("CARGO_BIN_EXE_runner"))
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .spawn()
+    .unwrap();
+  child.stdin.take().unwrap().write_all(b"0110\r\n01\r\n").unwrap();
+  let output = child.wait_with_output().unwrap();
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert_eq!(stdout, "0110\taccept\n01\treject\n");
+  assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn trace_like_main() {
+  let output = runner(&["--trace", "00"]);
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert_eq!(stdout, "[(0, [2])]
+[(1, [2, 0])]
+[(1, [2, 0, 0]), (2, [2])]
+[(3, [])]
+The input is accepted
+00\taccept
+");
+}
+
+#[test]
+fn words_that_are_not_binary() {
+  let output = runner(&["0120"]);
+  assert!(output.stdout.is_empty());
+  assert_eq!(output.status.code(), Some(2));
+}